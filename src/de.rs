@@ -1,9 +1,11 @@
+use crate::scope;
 use crate::wrap::{Wrap, WrapVariant};
-use crate::{Chain, Error, Track};
+use crate::{Chain, Error, NodeKind, Observer, Position, Scalar, Track, Tracker};
 use alloc::borrow::ToOwned as _;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt;
+use core::marker::PhantomData;
 use serde::de::{self, Deserialize, DeserializeSeed, Visitor};
 
 /// Entry point. See [crate documentation][crate] for an example.
@@ -12,11 +14,247 @@ where
     D: de::Deserializer<'de>,
     T: Deserialize<'de>,
 {
-    let mut track = Track::new();
-    match T::deserialize(Deserializer::new(deserializer, &mut track)) {
+    let track = Track::new();
+    deserialize_tracked(PhantomData, deserializer, Tracker::new(&track))
+}
+
+/// Entry point for stateful deserialization through a `DeserializeSeed`.
+///
+/// # Example
+///
+/// ```
+/// use serde::de::{DeserializeSeed, Deserializer};
+/// use serde::Deserialize;
+///
+/// // Deserializes a string, checking it against a set of allowed values.
+/// struct OneOf<'a>(&'a [&'a str]);
+///
+/// impl<'de, 'a> DeserializeSeed<'de> for OneOf<'a> {
+///     type Value = String;
+///
+///     fn deserialize<D>(self, deserializer: D) -> Result<String, D::Error>
+///     where
+///         D: Deserializer<'de>,
+///     {
+///         let s = String::deserialize(deserializer)?;
+///         if !self.0.contains(&s.as_str()) {
+///             return Err(serde::de::Error::custom(format!("unexpected {:?}", s)));
+///         }
+///         Ok(s)
+///     }
+/// }
+///
+/// // Seeds are free to carry state into nested values.
+/// struct List<'a>(&'a [&'a str]);
+///
+/// impl<'de, 'a> DeserializeSeed<'de> for List<'a> {
+///     type Value = Vec<String>;
+///
+///     fn deserialize<D>(self, deserializer: D) -> Result<Vec<String>, D::Error>
+///     where
+///         D: Deserializer<'de>,
+///     {
+///         deserializer.deserialize_seq(self)
+///     }
+/// }
+///
+/// impl<'de, 'a> serde::de::Visitor<'de> for List<'a> {
+///     type Value = Vec<String>;
+///
+///     fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+///         f.write_str("a list")
+///     }
+///
+///     fn visit_seq<A>(self, mut seq: A) -> Result<Vec<String>, A::Error>
+///     where
+///         A: serde::de::SeqAccess<'de>,
+///     {
+///         let mut vec = Vec::new();
+///         while let Some(s) = seq.next_element_seed(OneOf(self.0))? {
+///             vec.push(s);
+///         }
+///         Ok(vec)
+///     }
+/// }
+///
+/// let j = r#"["red", "green", "purple"]"#;
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+///
+/// let colors = ["red", "green", "blue"];
+/// let result = serde_path_to_error::deserialize_seed(List(&colors), jd);
+/// match result {
+///     Ok(_) => panic!("expected an unexpected value error"),
+///     Err(err) => assert_eq!(err.path().to_string(), "[2]"),
+/// }
+/// ```
+pub fn deserialize_seed<'de, S, D>(seed: S, deserializer: D) -> Result<S::Value, Error<D::Error>>
+where
+    S: DeserializeSeed<'de>,
+    D: de::Deserializer<'de>,
+{
+    let track = Track::new();
+    deserialize_tracked(seed, deserializer, Tracker::new(&track))
+}
+
+/// Entry point for deserializing into an existing value using
+/// `Deserialize::deserialize_in_place`.
+///
+/// Types that support it, such as `Vec` and `String`, reuse the allocation
+/// already held by `place`. If an error occurs, `place` is left in a valid but
+/// unspecified state.
+///
+/// # Example
+///
+/// ```
+/// let mut buffer: Vec<Vec<u32>> = Vec::with_capacity(1024);
+///
+/// let j = "[[1, 2], [3]]";
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+/// serde_path_to_error::deserialize_in_place(jd, &mut buffer).unwrap();
+/// assert_eq!(buffer, [vec![1, 2], vec![3]]);
+/// assert!(buffer.capacity() >= 1024);
+///
+/// let j = "[[1, 2], [3, -4]]";
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+/// let err = serde_path_to_error::deserialize_in_place(jd, &mut buffer).unwrap_err();
+/// assert_eq!(err.path().to_string(), "[1][1]");
+/// ```
+pub fn deserialize_in_place<'de, D, T>(
+    deserializer: D,
+    place: &mut T,
+) -> Result<(), Error<D::Error>>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let track = Track::new();
+    deserialize_tracked(InPlace(place), deserializer, Tracker::new(&track))
+}
+
+struct InPlace<'a, T>(&'a mut T);
+
+impl<'a, 'de, T> DeserializeSeed<'de> for InPlace<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        T::deserialize_in_place(deserializer, self.0)
+    }
+}
+
+/// Entry point that also records the input span of the value that failed to
+/// deserialize, available through [`Error::span`].
+///
+/// The `position` reports how far into the input the deserializer has read. It
+/// is typically shared with a reader that counts the bytes passing through it.
+/// The span then starts wherever the previous value ended, so it may include
+/// leading whitespace and separators, and ends wherever the deserializer had
+/// read up to when the error occurred. See [`Position`] for which sources of
+/// offsets give accurate spans; notably the reader must not be buffered.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// use serde_path_to_error::Position;
+/// use std::cell::Cell;
+/// use std::io::{self, Read};
+///
+/// struct CountingReader<'a, R> {
+///     inner: R,
+///     offset: &'a Cell<usize>,
+/// }
+///
+/// impl<'a, R: Read> Read for CountingReader<'a, R> {
+///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+///         let n = self.inner.read(buf)?;
+///         self.offset.set(self.offset.get() + n);
+///         Ok(n)
+///     }
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Package {
+///     name: String,
+///     version: String,
+/// }
+///
+/// let j = r#"{"name": "demo", "version": true}"#;
+///
+/// let offset = Cell::new(0);
+/// let reader = CountingReader {
+///     inner: j.as_bytes(),
+///     offset: &offset,
+/// };
+/// let jd = &mut serde_json::Deserializer::from_reader(reader);
+///
+/// let result: Result<Package, _> = serde_path_to_error::deserialize_with_position(jd, &offset);
+/// let err = match result {
+///     Ok(_) => panic!("expected a type error"),
+///     Err(err) => err,
+/// };
+/// let span = err.span().unwrap();
+/// assert_eq!(&j[span.start..span.end], " true");
+/// ```
+pub fn deserialize_with_position<'de, D, T>(
+    deserializer: D,
+    position: &dyn Position,
+) -> Result<T, Error<D::Error>>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let track = Track::new();
+    let tracker = Tracker::new(&track).with_position(position);
+    deserialize_tracked(PhantomData, deserializer, tracker)
+}
+
+/// Entry point that reports every node visited by the deserializer to the
+/// given observer.
+///
+/// See [`Observer`] for an example. Use [`Deserializer::with_observer`] to
+/// attach an observer when managing your own `Deserializer`.
+pub fn deserialize_observed<'de, D, T>(
+    deserializer: D,
+    observer: &dyn Observer,
+) -> Result<T, Error<D::Error>>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let track = Track::new();
+    let tracker = Tracker::new(&track).with_observer(observer);
+    deserialize_tracked(PhantomData, deserializer, tracker)
+}
+
+pub(crate) fn deserialize_tracked<'de, S, D>(
+    seed: S,
+    deserializer: D,
+    track: Tracker,
+) -> Result<S::Value, Error<D::Error>>
+where
+    S: DeserializeSeed<'de>,
+    D: de::Deserializer<'de>,
+{
+    let result = {
+        let _scope = scope::enter(&Chain::Root, &track);
+        seed.deserialize(Deserializer {
+            de: deserializer,
+            chain: Chain::Root,
+            track,
+        })
+    };
+    match result {
         Ok(t) => Ok(t),
         Err(err) => Err(Error {
-            path: track.path(),
+            span: track.span(),
+            path: track.take_path(),
             original: err,
         }),
     }
@@ -69,9 +307,9 @@ where
 /// }
 /// ```
 pub struct Deserializer<'a, 'b, D> {
-    de: D,
-    chain: Chain<'a>,
-    track: &'b Track,
+    pub(crate) de: D,
+    pub(crate) chain: Chain<'a>,
+    pub(crate) track: Tracker<'b>,
 }
 
 impl<'a, 'b, D> Deserializer<'a, 'b, D> {
@@ -80,7 +318,26 @@ impl<'a, 'b, D> Deserializer<'a, 'b, D> {
         Deserializer {
             de,
             chain: Chain::Root,
-            track,
+            track: Tracker::new(track),
+        }
+    }
+
+    /// Reports every node visited by the deserializer to the given observer.
+    pub fn with_observer(self, observer: &'b dyn Observer) -> Self {
+        Deserializer {
+            track: self.track.with_observer(observer),
+            ..self
+        }
+    }
+
+    /// Records the span of the innermost value that fails to deserialize, by
+    /// asking the given position for the current input offset as each value
+    /// is started and when it fails. The span is available afterwards through
+    /// [`Track::span`].
+    pub fn with_position(self, position: &'b dyn Position) -> Self {
+        Deserializer {
+            track: self.track.with_position(position),
+            ..self
         }
     }
 }
@@ -98,9 +355,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_any(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -109,9 +368,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_bool(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -120,9 +381,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_u8(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -131,9 +394,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_u16(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -142,9 +407,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_u32(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -153,9 +420,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_u64(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -164,9 +433,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_u128(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -175,9 +446,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_i8(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -186,9 +459,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_i16(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -197,9 +472,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_i32(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -208,9 +485,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_i64(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -219,9 +498,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_i128(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -230,9 +511,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_f32(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -241,9 +524,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_f64(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -252,9 +537,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_char(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -263,9 +550,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_str(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -274,9 +563,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_string(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -285,9 +576,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_bytes(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -296,9 +589,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_byte_buf(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -307,9 +602,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_option(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -318,9 +615,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_unit(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_unit_struct<V>(
@@ -333,9 +632,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_unit_struct(name, Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_newtype_struct<V>(
@@ -348,9 +649,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_newtype_struct(name, Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -359,9 +662,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_seq(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, D::Error>
@@ -370,9 +675,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_tuple(len, Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_tuple_struct<V>(
@@ -386,9 +693,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_tuple_struct(name, len, Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -397,9 +706,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_map(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_struct<V>(
@@ -413,9 +724,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_struct(name, fields, Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_enum<V>(
@@ -429,9 +742,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_enum(name, variants, Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -440,9 +755,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_ignored_any(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, D::Error>
@@ -451,9 +768,11 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let start = track.offset();
+        track.deserialize_node(&chain)?;
         self.de
             .deserialize_identifier(Wrap::new(visitor, &chain, track))
-            .map_err(|err| track.trigger(&chain, err))
+            .map_err(|err| track.trigger_node(&chain, start, err))
     }
 
     fn is_human_readable(&self) -> bool {
@@ -478,6 +797,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::Bool(v));
         self.delegate
             .visit_bool(v)
             .map_err(|err| track.trigger(chain, err))
@@ -489,6 +809,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::I64(i64::from(v)));
         self.delegate
             .visit_i8(v)
             .map_err(|err| track.trigger(chain, err))
@@ -500,6 +821,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::I64(i64::from(v)));
         self.delegate
             .visit_i16(v)
             .map_err(|err| track.trigger(chain, err))
@@ -511,6 +833,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::I64(i64::from(v)));
         self.delegate
             .visit_i32(v)
             .map_err(|err| track.trigger(chain, err))
@@ -522,6 +845,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::I64(v));
         self.delegate
             .visit_i64(v)
             .map_err(|err| track.trigger(chain, err))
@@ -533,6 +857,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::I128(v));
         self.delegate
             .visit_i128(v)
             .map_err(|err| track.trigger(chain, err))
@@ -544,6 +869,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::U64(u64::from(v)));
         self.delegate
             .visit_u8(v)
             .map_err(|err| track.trigger(chain, err))
@@ -555,6 +881,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::U64(u64::from(v)));
         self.delegate
             .visit_u16(v)
             .map_err(|err| track.trigger(chain, err))
@@ -566,6 +893,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::U64(u64::from(v)));
         self.delegate
             .visit_u32(v)
             .map_err(|err| track.trigger(chain, err))
@@ -577,6 +905,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::U64(v));
         self.delegate
             .visit_u64(v)
             .map_err(|err| track.trigger(chain, err))
@@ -588,6 +917,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::U128(v));
         self.delegate
             .visit_u128(v)
             .map_err(|err| track.trigger(chain, err))
@@ -599,6 +929,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::F64(f64::from(v)));
         self.delegate
             .visit_f32(v)
             .map_err(|err| track.trigger(chain, err))
@@ -610,6 +941,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::F64(v));
         self.delegate
             .visit_f64(v)
             .map_err(|err| track.trigger(chain, err))
@@ -621,6 +953,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::Char(v));
         self.delegate
             .visit_char(v)
             .map_err(|err| track.trigger(chain, err))
@@ -632,6 +965,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::Str(v));
         self.delegate
             .visit_str(v)
             .map_err(|err| track.trigger(chain, err))
@@ -643,6 +977,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::Str(v));
         self.delegate
             .visit_borrowed_str(v)
             .map_err(|err| track.trigger(chain, err))
@@ -654,6 +989,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::Str(&v));
         self.delegate
             .visit_string(v)
             .map_err(|err| track.trigger(chain, err))
@@ -665,6 +1001,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::Unit);
         self.delegate
             .visit_unit()
             .map_err(|err| track.trigger(chain, err))
@@ -676,6 +1013,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::None);
         self.delegate
             .visit_none()
             .map_err(|err| track.trigger(chain, err))
@@ -717,9 +1055,13 @@ where
     {
         let chain = self.chain;
         let track = self.track;
-        self.delegate
+        track.enter(chain, NodeKind::Seq);
+        let result = self
+            .delegate
             .visit_seq(SeqAccess::new(visitor, chain, track))
-            .map_err(|err| track.trigger(chain, err))
+            .map_err(|err| track.trigger(chain, err));
+        track.exit(chain);
+        result
     }
 
     fn visit_map<V>(self, visitor: V) -> Result<Self::Value, V::Error>
@@ -728,9 +1070,13 @@ where
     {
         let chain = self.chain;
         let track = self.track;
-        self.delegate
+        track.enter(chain, NodeKind::Map);
+        let result = self
+            .delegate
             .visit_map(MapAccess::new(visitor, chain, track))
-            .map_err(|err| track.trigger(chain, err))
+            .map_err(|err| track.trigger(chain, err));
+        track.exit(chain);
+        result
     }
 
     fn visit_enum<V>(self, visitor: V) -> Result<Self::Value, V::Error>
@@ -739,9 +1085,13 @@ where
     {
         let chain = self.chain;
         let track = self.track;
-        self.delegate
+        track.enter(chain, NodeKind::Enum);
+        let result = self
+            .delegate
             .visit_enum(Wrap::new(visitor, chain, track))
-            .map_err(|err| track.trigger(chain, err))
+            .map_err(|err| track.trigger(chain, err));
+        track.exit(chain);
+        result
     }
 
     fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
@@ -750,6 +1100,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::Bytes(v));
         self.delegate
             .visit_bytes(v)
             .map_err(|err| track.trigger(chain, err))
@@ -761,6 +1112,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::Bytes(v));
         self.delegate
             .visit_borrowed_bytes(v)
             .map_err(|err| track.trigger(chain, err))
@@ -772,6 +1124,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(chain, Scalar::Bytes(&v));
         self.delegate
             .visit_byte_buf(v)
             .map_err(|err| track.trigger(chain, err))
@@ -819,6 +1172,7 @@ where
     fn unit_variant(self) -> Result<(), X::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.leaf(&chain, Scalar::Unit);
         self.delegate
             .unit_variant()
             .map_err(|err| track.trigger(&chain, err))
@@ -842,6 +1196,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let _scope = scope::enter(&chain, &track);
         self.delegate
             .tuple_variant(len, Wrap::new(visitor, &chain, track))
             .map_err(|err| track.trigger(&chain, err))
@@ -857,6 +1212,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let _scope = scope::enter(&chain, &track);
         self.delegate
             .struct_variant(fields, Wrap::new(visitor, &chain, track))
             .map_err(|err| track.trigger(&chain, err))
@@ -1396,11 +1752,11 @@ where
 struct TrackedSeed<'a, 'b, X> {
     seed: X,
     chain: Chain<'a>,
-    track: &'b Track,
+    track: Tracker<'b>,
 }
 
 impl<'a, 'b, X> TrackedSeed<'a, 'b, X> {
-    fn new(seed: X, chain: Chain<'a>, track: &'b Track) -> Self {
+    fn new(seed: X, chain: Chain<'a>, track: Tracker<'b>) -> Self {
         TrackedSeed { seed, chain, track }
     }
 }
@@ -1417,6 +1773,7 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        let _scope = scope::enter(&chain, &track);
         self.seed
             .deserialize(Deserializer {
                 de: deserializer,
@@ -1432,11 +1789,11 @@ struct SeqAccess<'a, 'b, X> {
     delegate: X,
     chain: &'a Chain<'a>,
     index: usize,
-    track: &'b Track,
+    track: Tracker<'b>,
 }
 
 impl<'a, 'b, X> SeqAccess<'a, 'b, X> {
-    fn new(delegate: X, chain: &'a Chain<'a>, track: &'b Track) -> Self {
+    fn new(delegate: X, chain: &'a Chain<'a>, track: Tracker<'b>) -> Self {
         SeqAccess {
             delegate,
             chain,
@@ -1480,11 +1837,11 @@ struct MapAccess<'a, 'b, X> {
     delegate: X,
     chain: &'a Chain<'a>,
     key: Option<String>,
-    track: &'b Track,
+    track: Tracker<'b>,
 }
 
 impl<'a, 'b, X> MapAccess<'a, 'b, X> {
-    fn new(delegate: X, chain: &'a Chain<'a>, track: &'b Track) -> Self {
+    fn new(delegate: X, chain: &'a Chain<'a>, track: Tracker<'b>) -> Self {
         MapAccess {
             delegate,
             chain,
@@ -0,0 +1,333 @@
+use crate::hook::Hook;
+use crate::path::{Path, Segment};
+use crate::walk::{walk, ScalarValue, WalkError};
+use crate::{Error, NodeKind, Scalar};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString as _};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt::{self, Display};
+use serde::ser::Serialize;
+
+/// Compares two values, listing the paths at which they differ.
+///
+/// Both values are walked as they would be serialized, without serializing to
+/// any format. Map entries and struct fields are matched by key and sequence
+/// elements by index. A value that changes from one kind to another, such as
+/// from `None` to a struct or from one enum variant to another, is reported
+/// once at its own path rather than once per leaf inside of it.
+///
+/// Fails if serializing either value fails, or if either value contains a map
+/// whose keys are not strings, integers, booleans or unit variants, as the
+/// entries of such a map have no path by which to match them up.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Serialize;
+/// #
+/// use std::collections::BTreeMap as Map;
+///
+/// #[derive(Serialize)]
+/// struct Package {
+///     name: String,
+///     dependencies: Map<String, Dependency>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Dependency {
+///     version: String,
+/// }
+///
+/// let v1 = Package {
+///     name: "demo".to_owned(),
+///     dependencies: Map::from([
+///         ("serde".to_owned(), Dependency { version: "1.0".to_owned() }),
+///         ("log".to_owned(), Dependency { version: "0.4".to_owned() }),
+///     ]),
+/// };
+///
+/// let v2 = Package {
+///     name: "demo".to_owned(),
+///     dependencies: Map::from([
+///         ("serde".to_owned(), Dependency { version: "1.1".to_owned() }),
+///     ]),
+/// };
+///
+/// let changes: Vec<String> = serde_path_to_error::diff(&v1, &v2)
+///     .unwrap()
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+///
+/// assert_eq!(
+///     changes,
+///     [
+///         r#"dependencies.log: removed {1 entry}"#,
+///         r#"dependencies.serde.version: "1.0" -> "1.1""#,
+///     ],
+/// );
+/// ```
+pub fn diff<A, B>(old: &A, new: &B) -> Result<Vec<Change>, Error<WalkError>>
+where
+    A: ?Sized + Serialize,
+    B: ?Sized + Serialize,
+{
+    let old = tree(old)?;
+    let new = tree(new)?;
+    let mut changes = Vec::new();
+    compare(&mut Vec::new(), &old, &new, &mut changes);
+    Ok(changes)
+}
+
+/// Difference at a single path, found by [`diff`].
+#[derive(Clone, Debug)]
+pub enum Change {
+    /// The path is only present in the new value.
+    Added { path: Path, new: Shape },
+    /// The path is only present in the old value.
+    Removed { path: Path, old: Shape },
+    /// The path holds a different scalar, or a different kind of value.
+    Modified { path: Path, old: Shape, new: Shape },
+}
+
+impl Change {
+    /// The path at which the values differ.
+    pub fn path(&self) -> &Path {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Modified { path, .. } => path,
+        }
+    }
+}
+
+/// Value at the path of a [`Change`]: a scalar, or an outline of a compound
+/// value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Scalar(ScalarValue),
+    Seq { len: usize },
+    Map { len: usize },
+    Variant { name: String },
+}
+
+impl Display for Change {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Added { path, new } => write!(formatter, "{}: added {}", path, new),
+            Change::Removed { path, old } => write!(formatter, "{}: removed {}", path, old),
+            Change::Modified { path, old, new } => {
+                write!(formatter, "{}: {} -> {}", path, old, new)
+            }
+        }
+    }
+}
+
+impl Display for Shape {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Shape::Scalar(scalar) => write!(formatter, "{}", scalar),
+            Shape::Seq { len: 1 } => formatter.write_str("[1 element]"),
+            Shape::Seq { len } => write!(formatter, "[{} elements]", len),
+            Shape::Map { len: 1 } => formatter.write_str("{1 entry}"),
+            Shape::Map { len } => write!(formatter, "{{{} entries}}", len),
+            Shape::Variant { name } => write!(formatter, "{}(..)", name),
+        }
+    }
+}
+
+enum Node {
+    Scalar(ScalarValue),
+    Seq(Vec<Node>),
+    // Keyed by the string form of the key, in serialization order.
+    Map(Vec<(String, Node)>),
+    Variant(String, Box<Node>),
+}
+
+impl Node {
+    fn shape(&self) -> Shape {
+        match self {
+            Node::Scalar(scalar) => Shape::Scalar(scalar.clone()),
+            Node::Seq(elements) => Shape::Seq {
+                len: elements.len(),
+            },
+            Node::Map(entries) => Shape::Map { len: entries.len() },
+            Node::Variant(name, _node) => Shape::Variant { name: name.clone() },
+        }
+    }
+}
+
+fn compare(path: &mut Vec<Segment>, old: &Node, new: &Node, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Node::Scalar(a), Node::Scalar(b)) if a == b => {}
+        (Node::Seq(a), Node::Seq(b)) => {
+            for index in 0..a.len().max(b.len()) {
+                path.push(Segment::Seq { index });
+                child(path, a.get(index), b.get(index), changes);
+                path.pop();
+            }
+        }
+        (Node::Map(a), Node::Map(b)) => {
+            // Entries of the new map by key. A key that appears more than once
+            // is matched by its first occurrence.
+            let mut index = BTreeMap::new();
+            for (i, (key, _node)) in b.iter().enumerate().rev() {
+                index.insert(key.as_str(), i);
+            }
+            let mut matched = vec![false; b.len()];
+            for (key, old) in a {
+                let new = index.remove(key.as_str()).map(|i| {
+                    matched[i] = true;
+                    &b[i].1
+                });
+                path.push(Segment::Map { key: key.clone() });
+                child(path, Some(old), new, changes);
+                path.pop();
+            }
+            for ((key, new), matched) in b.iter().zip(matched) {
+                if !matched {
+                    path.push(Segment::Map { key: key.clone() });
+                    child(path, None, Some(new), changes);
+                    path.pop();
+                }
+            }
+        }
+        (Node::Variant(a, old), Node::Variant(b, new)) if a == b => {
+            compare(path, old, new, changes);
+        }
+        _ => child(path, Some(old), Some(new), changes),
+    }
+}
+
+fn child(
+    path: &mut Vec<Segment>,
+    old: Option<&Node>,
+    new: Option<&Node>,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (Some(old), Some(new)) if same_kind(old, new) => compare(path, old, new, changes),
+        (Some(old), Some(new)) => changes.push(Change::Modified {
+            path: path.iter().cloned().collect(),
+            old: old.shape(),
+            new: new.shape(),
+        }),
+        (None, Some(new)) => changes.push(Change::Added {
+            path: path.iter().cloned().collect(),
+            new: new.shape(),
+        }),
+        (Some(old), None) => changes.push(Change::Removed {
+            path: path.iter().cloned().collect(),
+            old: old.shape(),
+        }),
+        (None, None) => {}
+    }
+}
+
+fn same_kind(old: &Node, new: &Node) -> bool {
+    match (old, new) {
+        (Node::Scalar(a), Node::Scalar(b)) => a == b,
+        (Node::Seq(_), Node::Seq(_)) | (Node::Map(_), Node::Map(_)) => true,
+        (Node::Variant(a, _), Node::Variant(b, _)) => a == b,
+        _ => false,
+    }
+}
+
+fn tree<T>(value: &T) -> Result<Node, Error<WalkError>>
+where
+    T: ?Sized + Serialize,
+{
+    let build = Build {
+        stack: RefCell::new(Vec::new()),
+        variants: RefCell::new(Vec::new()),
+        root: RefCell::new(None),
+    };
+    walk(value, &build)?;
+    let root = build.root.into_inner();
+    Ok(root.unwrap_or(Node::Scalar(ScalarValue::Unit)))
+}
+
+// Reassembles the events of a walk into a tree.
+struct Build {
+    stack: RefCell<Vec<Frame>>,
+    // Variants whose contents have not started yet.
+    variants: RefCell<Vec<String>>,
+    root: RefCell<Option<Node>>,
+}
+
+struct Frame {
+    key: Option<String>,
+    variants: Vec<String>,
+    node: Node,
+}
+
+impl Build {
+    fn attach(&self, key: Option<String>, variants: Vec<String>, mut node: Node) {
+        for variant in variants.into_iter().rev() {
+            node = Node::Variant(variant, Box::new(node));
+        }
+        match self.stack.borrow_mut().last_mut() {
+            Some(Frame {
+                node: Node::Seq(elements),
+                ..
+            }) => elements.push(node),
+            Some(Frame {
+                node: Node::Map(entries),
+                ..
+            }) => {
+                // Keys that are not strings were already rejected by
+                // `check_key`.
+                entries.push((key.unwrap_or_default(), node));
+            }
+            Some(_) => unreachable!(),
+            None => *self.root.borrow_mut() = Some(node),
+        }
+    }
+}
+
+fn key(path: &Path) -> Option<String> {
+    match path.iter().next_back() {
+        Some(Segment::Map { key }) => Some(key.clone()),
+        _ => None,
+    }
+}
+
+impl Hook for Build {
+    fn enter(&self, path: &Path, kind: NodeKind) {
+        let node = match kind {
+            NodeKind::Seq => Node::Seq(Vec::new()),
+            NodeKind::Map | NodeKind::Enum => Node::Map(Vec::new()),
+        };
+        self.stack.borrow_mut().push(Frame {
+            key: key(path),
+            variants: self.variants.take(),
+            node,
+        });
+    }
+
+    fn leaf(&self, path: &Path, scalar: Scalar) {
+        let variants = self.variants.take();
+        self.attach(key(path), variants, Node::Scalar(ScalarValue::from(scalar)));
+    }
+
+    fn check_key(&self, path: &Path, _string: bool) -> Result<(), String> {
+        match key(path) {
+            Some(_) => Ok(()),
+            None => Err("cannot compare map entries whose key is not a string, integer, boolean or unit variant".to_string()),
+        }
+    }
+
+    fn exit(&self, _path: &Path) {
+        let frame = self.stack.borrow_mut().pop();
+        if let Some(frame) = frame {
+            self.attach(frame.key, frame.variants, frame.node);
+        }
+    }
+
+    fn variant(&self, _path: &Path, variant: &str) {
+        self.variants.borrow_mut().push(variant.to_string());
+    }
+}
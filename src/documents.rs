@@ -0,0 +1,161 @@
+use crate::de::deserialize_tracked;
+use crate::path::{Path, Segment};
+use crate::{Error, Track, Tracker};
+use core::fmt::{self, Display};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use serde::de::{self, Deserialize};
+use serde::ser::StdError;
+
+/// Entry point for a stream of several documents, each read by its own
+/// `Deserializer`, such as the documents of a multi-document YAML file.
+///
+/// Yields the result of deserializing every document in turn. Each error is a
+/// [`DocumentError`] holding the index of the document that failed alongside
+/// the path within that document. A document that fails does not stop the
+/// ones after it from being deserialized.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// #[derive(Deserialize, Debug)]
+/// struct Record {
+///     id: u64,
+/// }
+///
+/// let values = vec![
+///     serde_json::json!({"id": 1}),
+///     serde_json::json!({"id": "2"}),
+///     serde_json::json!({"id": 3}),
+/// ];
+///
+/// let results: Vec<Result<Record, _>> = serde_path_to_error::documents(values).collect();
+/// assert!(results[0].is_ok());
+/// let err = results[1].as_ref().unwrap_err();
+/// assert_eq!(err.index(), 1);
+/// assert_eq!(err.path().to_string(), "id");
+/// assert!(results[2].is_ok());
+/// ```
+pub fn documents<'de, I, T>(deserializers: I) -> Documents<'de, I::IntoIter, T>
+where
+    I: IntoIterator,
+    I::Item: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Documents {
+        iter: deserializers.into_iter(),
+        index: 0,
+        marker: PhantomData,
+    }
+}
+
+/// Iterator returned by [`documents`].
+pub struct Documents<'de, I, T> {
+    iter: I,
+    index: usize,
+    marker: PhantomData<fn(&'de ()) -> T>,
+}
+
+impl<'de, I, T> Iterator for Documents<'de, I, T>
+where
+    I: Iterator,
+    I::Item: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    type Item = Result<T, DocumentError<<I::Item as de::Deserializer<'de>>::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let deserializer = self.iter.next()?;
+        let index = self.index;
+        self.index += 1;
+        Some(deserialize_document(deserializer, index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'de, I, T> FusedIterator for Documents<'de, I, T>
+where
+    I: FusedIterator,
+    I::Item: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+}
+
+/// Error from one document of a stream, returned by [`documents`] and by the
+/// iterators of the [`json`][crate::json] module.
+///
+/// Displayed as the index of the document followed by the path within it, like
+/// `#12.items[3].id: invalid type: ...`, so that the two read as a single path
+/// into the stream.
+#[derive(Clone, Debug)]
+pub struct DocumentError<E> {
+    index: usize,
+    error: Error<E>,
+}
+
+impl<E> DocumentError<E> {
+    /// Error of the document at `index` within the stream, counting from 0.
+    pub fn new(index: usize, error: Error<E>) -> Self {
+        DocumentError { index, error }
+    }
+
+    /// Index of the document that failed within the stream, counting from 0.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Path at which the error occurred, relative to the document.
+    pub fn path(&self) -> &Path {
+        self.error.path()
+    }
+
+    /// Reference to the error within the document.
+    pub fn error(&self) -> &Error<E> {
+        &self.error
+    }
+
+    /// The error within the document.
+    pub fn into_error(self) -> Error<E> {
+        self.error
+    }
+}
+
+impl<E: Display> Display for DocumentError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.index)?;
+        let path = self.error.path();
+        if !path.is_only_unknown() {
+            if !matches!(path.iter().next(), Some(Segment::Seq { .. })) {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", path)?;
+        }
+        write!(f, ": {}", self.error.inner())
+    }
+}
+
+impl<E: StdError> StdError for DocumentError<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+pub(crate) fn deserialize_document<'de, D, T>(
+    deserializer: D,
+    index: usize,
+) -> Result<T, DocumentError<D::Error>>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let track = Track::new();
+    match deserialize_tracked(PhantomData, deserializer, Tracker::new(&track)) {
+        Ok(t) => Ok(t),
+        Err(err) => Err(DocumentError::new(index, err)),
+    }
+}
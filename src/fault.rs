@@ -0,0 +1,154 @@
+use crate::de::deserialize_tracked;
+use crate::hook::Hook;
+use crate::path::Path;
+use crate::pattern::{PathPattern, PatternError};
+use crate::ser::serialize_tracked;
+use crate::{Error, Track, Tracker};
+use alloc::string::{String, ToString as _};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use serde::de::{self, Deserialize};
+use serde::ser::{self, Serialize};
+
+/// Makes serialization or deserialization fail at a chosen value, for testing
+/// how code handles errors.
+///
+/// The fault is raised through the format's own error type, by way of its
+/// `custom` constructor, as the chosen value is started and before the
+/// serializer or deserializer sees it. The error carries the value's path like
+/// any other.
+///
+/// Values are chosen either by path, or by their index in the order in which
+/// values are started. Every value counts, starting from the root at index 0,
+/// and the count starts over on every call. An `Option` that is `Some` counts
+/// as two values at the same path: the option itself, then its contents.
+/// Likewise a newtype struct or newtype variant counts once for itself and once
+/// for its contents. Index `n` thus always fails after exactly `n` values have
+/// been started, counting the same way whether serializing or deserializing.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// use serde_path_to_error::Fault;
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Package {
+///     name: String,
+///     dependencies: Vec<String>,
+/// }
+///
+/// let j = r#"{"name": "demo", "dependencies": ["serde", "log"]}"#;
+///
+/// let fault = Fault::at_path("dependencies[1]")?.message("disk on fire");
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+/// let err = fault.deserialize::<_, Package>(jd).unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "dependencies[1]: disk on fire at line 1 column 43",
+/// );
+///
+/// // Without the fault the input deserializes fine.
+/// let package: Package = serde_json::from_str(j).unwrap();
+/// assert_eq!(package.dependencies, ["serde", "log"]);
+/// # Ok::<(), serde_path_to_error::PatternError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Fault {
+    target: Target,
+    message: String,
+}
+
+#[derive(Clone, Debug)]
+enum Target {
+    Path(PathPattern),
+    Node(usize),
+}
+
+impl Fault {
+    /// Fails at the first value whose path matches the given pattern. Fails if
+    /// the pattern does not parse.
+    pub fn at_path(pattern: &str) -> Result<Self, PatternError> {
+        Ok(Fault::new(Target::Path(pattern.parse()?)))
+    }
+
+    /// Fails at the value with the given index, counting from 0 in the order
+    /// in which values are started.
+    pub fn at_node(index: usize) -> Self {
+        Fault::new(Target::Node(index))
+    }
+
+    fn new(target: Target) -> Self {
+        Fault {
+            target,
+            message: "injected fault".to_string(),
+        }
+    }
+
+    /// Uses the given message for the error instead of `"injected fault"`.
+    pub fn message(self, message: &str) -> Self {
+        Fault {
+            message: message.to_string(),
+            ..self
+        }
+    }
+
+    /// Serializes the value with the given serializer, failing at the chosen
+    /// value.
+    pub fn serialize<T, S>(&self, value: &T, serializer: S) -> Result<S::Ok, Error<S::Error>>
+    where
+        T: ?Sized + Serialize,
+        S: ser::Serializer,
+    {
+        let run = Run::new(self);
+        serialize_tracked(serializer, Some(&run), |serializer| {
+            value.serialize(serializer)
+        })
+    }
+
+    /// Deserializes a value from the given deserializer, failing at the chosen
+    /// value.
+    pub fn deserialize<'de, D, T>(&self, deserializer: D) -> Result<T, Error<D::Error>>
+    where
+        D: de::Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let run = Run::new(self);
+        let track = Track::new();
+        let tracker = Tracker::new(&track).with_hook(&run);
+        deserialize_tracked(PhantomData, deserializer, tracker)
+    }
+}
+
+// A single serialization or deserialization failing at the fault's value,
+// counting values as they are started.
+struct Run<'a> {
+    fault: &'a Fault,
+    count: Cell<usize>,
+}
+
+impl<'a> Run<'a> {
+    fn new(fault: &'a Fault) -> Self {
+        Run {
+            fault,
+            count: Cell::new(0),
+        }
+    }
+}
+
+impl<'a> Hook for Run<'a> {
+    fn node(&self, path: &Path) -> Result<(), String> {
+        let index = self.count.get();
+        self.count.set(index + 1);
+        let hit = match &self.fault.target {
+            Target::Path(pattern) => pattern.matches(path),
+            Target::Node(node) => index == *node,
+        };
+        if hit {
+            Err(self.fault.message.clone())
+        } else {
+            Ok(())
+        }
+    }
+}
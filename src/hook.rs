@@ -0,0 +1,66 @@
+use crate::path::Path;
+use crate::{NodeKind, Scalar};
+use alloc::string::String;
+
+// Extension point for the adapters built on top of the Serializer and
+// Deserializer wrappers, attached through `Tracker::with_hook`. Except for
+// `node`, only the Serializer wrapper consults the hook.
+pub(crate) trait Hook {
+    // Checked as every value is started, before anything else happens to it.
+    // An error fails at the value's path with the given message.
+    fn node(&self, path: &Path) -> Result<(), String> {
+        let _ = path;
+        Ok(())
+    }
+
+    // String to serialize in place of the value at the given path, if any.
+    // Consulted for every element, map value and struct field.
+    fn replace(&self, path: &Path) -> Option<&str> {
+        let _ = path;
+        None
+    }
+
+    // Called before the elements of a sequence, map or struct are serialized.
+    fn enter(&self, path: &Path, kind: NodeKind) {
+        let _ = (path, kind);
+    }
+
+    // Called for every scalar value.
+    fn leaf(&self, path: &Path, scalar: Scalar) {
+        let _ = (path, scalar);
+    }
+
+    // Checked before every scalar value is serialized. An error fails
+    // serialization at the value's path with the given message.
+    fn check(&self, path: &Path, scalar: Scalar) -> Result<(), String> {
+        let _ = (path, scalar);
+        Ok(())
+    }
+
+    // Checked before every map key is serialized, with the path of the entry
+    // and whether the key serializes as a string. The scalars of the key are
+    // then passed to `check` at the same path.
+    fn check_key(&self, path: &Path, string: bool) -> Result<(), String> {
+        let _ = (path, string);
+        Ok(())
+    }
+
+    // Called after the elements of a sequence, map or struct are serialized.
+    fn exit(&self, path: &Path) {
+        let _ = path;
+    }
+
+    // Checked after the elements of a sequence, map or struct are serialized,
+    // before it is ended. An error fails serialization at its path with the
+    // given message.
+    fn check_exit(&self, path: &Path) -> Result<(), String> {
+        let _ = path;
+        Ok(())
+    }
+
+    // Called before the contents of a newtype, tuple or struct variant, which
+    // are at the same path as the enum.
+    fn variant(&self, path: &Path, variant: &str) {
+        let _ = (path, variant);
+    }
+}
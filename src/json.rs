@@ -0,0 +1,272 @@
+//! Shorthands for deserializing and serializing JSON with path tracking.
+//!
+//! These mirror the functions of the same name in `serde_json`, but report
+//! errors as [`Error<serde_json::Error>`][Error] carrying the path at which the
+//! error occurred.
+//!
+//! # Example
+//!
+//! ```
+//! # use serde_derive::Deserialize;
+//! #
+//! #[derive(Deserialize)]
+//! struct Package {
+//!     name: String,
+//! }
+//!
+//! let j = r#"{"name": 1}"#;
+//!
+//! let result: Result<Package, _> = serde_path_to_error::json::from_str(j);
+//! match result {
+//!     Ok(_) => panic!("expected a type error"),
+//!     Err(err) => assert_eq!(err.path().to_string(), "name"),
+//! }
+//! ```
+
+use crate::documents::{deserialize_document, DocumentError};
+use crate::{Error, Path};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use serde::de::{Deserialize, DeserializeOwned};
+use serde::ser::Serialize;
+use serde_json::de::{IoRead, Read, SliceRead, StrRead};
+use serde_json::Value;
+use std::io::{self, BufRead as _};
+
+/// Deserializes an instance of type `T` from a string of JSON text.
+///
+/// Like `serde_json::from_str`, fails if anything other than whitespace
+/// follows the value. That error has an empty path.
+pub fn from_str<'a, T>(s: &'a str) -> Result<T, Error<serde_json::Error>>
+where
+    T: Deserialize<'a>,
+{
+    from_trait(StrRead::new(s))
+}
+
+/// Deserializes an instance of type `T` from bytes of JSON text.
+///
+/// Like `serde_json::from_slice`, fails if anything other than whitespace
+/// follows the value. That error has an empty path.
+pub fn from_slice<'a, T>(v: &'a [u8]) -> Result<T, Error<serde_json::Error>>
+where
+    T: Deserialize<'a>,
+{
+    from_trait(SliceRead::new(v))
+}
+
+/// Deserializes an instance of type `T` from an I/O stream of JSON.
+///
+/// Like `serde_json::from_reader`, fails if anything other than whitespace
+/// follows the value, and does not buffer the reader.
+pub fn from_reader<R, T>(rdr: R) -> Result<T, Error<serde_json::Error>>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    from_trait(IoRead::new(rdr))
+}
+
+/// Interprets a `serde_json::Value` as an instance of type `T`.
+pub fn from_value<T>(value: Value) -> Result<T, Error<serde_json::Error>>
+where
+    T: DeserializeOwned,
+{
+    crate::deserialize(value)
+}
+
+fn from_trait<'de, R, T>(read: R) -> Result<T, Error<serde_json::Error>>
+where
+    R: Read<'de>,
+    T: Deserialize<'de>,
+{
+    let mut de = serde_json::Deserializer::new(read);
+    let value = crate::deserialize(&mut de)?;
+    de.end()
+        .map_err(|err| Error::new(Path::empty(), err).escape())?;
+    Ok(value)
+}
+
+/// Deserializes newline-delimited JSON from a string: every line that is not
+/// blank holds one value.
+///
+/// Errors are [`DocumentError`]s holding the index of the value that failed,
+/// counting from 0 and skipping blank lines, together with the path within
+/// that value. A value that fails to deserialize does not stop the values on
+/// the lines after it from being deserialized. Line and column numbers within
+/// the inner error count from the start of the value's own line.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// #[derive(Deserialize, Debug)]
+/// struct Record {
+///     id: u64,
+///     tags: Vec<String>,
+/// }
+///
+/// let ndjson = r#"
+///     {"id": 1, "tags": []}
+///     {"id": 2, "tags": ["a", 1]}
+///     {"id": 3, "tags": ["b"]}
+/// "#;
+///
+/// let mut records = serde_path_to_error::json::documents_from_str::<Record>(ndjson);
+/// assert_eq!(records.next().unwrap().unwrap().id, 1);
+/// let err = records.next().unwrap().unwrap_err();
+/// assert_eq!(err.index(), 1);
+/// assert_eq!(err.path().to_string(), "tags[1]");
+/// assert_eq!(records.next().unwrap().unwrap().id, 3);
+/// assert!(records.next().is_none());
+/// ```
+pub fn documents_from_str<'a, T>(s: &'a str) -> Lines<&'a [u8], T>
+where
+    T: Deserialize<'a>,
+{
+    Lines::new(s.as_bytes())
+}
+
+/// Deserializes newline-delimited JSON from bytes of JSON text. See
+/// [`documents_from_str`].
+pub fn documents_from_slice<'a, T>(v: &'a [u8]) -> Lines<&'a [u8], T>
+where
+    T: Deserialize<'a>,
+{
+    Lines::new(v)
+}
+
+/// Deserializes newline-delimited JSON from an I/O stream. See
+/// [`documents_from_str`].
+///
+/// The stream is read one line at a time. An I/O error is reported as the
+/// error of the value being read at the time and ends the iteration.
+pub fn documents_from_reader<R, T>(rdr: R) -> Lines<io::BufReader<R>, T>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    Lines::new(io::BufReader::new(rdr))
+}
+
+/// Iterator over the values of newline-delimited JSON, returned by
+/// [`documents_from_str`] and friends.
+pub struct Lines<R, T> {
+    read: R,
+    index: usize,
+    failed: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<R, T> Lines<R, T> {
+    fn new(read: R) -> Self {
+        Lines {
+            read,
+            index: 0,
+            failed: false,
+            marker: PhantomData,
+        }
+    }
+
+    fn document<'de>(&mut self, line: &'de [u8]) -> Result<T, DocumentError<serde_json::Error>>
+    where
+        T: Deserialize<'de>,
+    {
+        let index = self.index;
+        self.index += 1;
+        let mut de = serde_json::Deserializer::from_slice(line);
+        let value = deserialize_document(&mut de, index)?;
+        match de.end() {
+            Ok(()) => Ok(value),
+            Err(err) => Err(DocumentError::new(
+                index,
+                Error::new(Path::empty(), err).escape(),
+            )),
+        }
+    }
+}
+
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+impl<'a, T> Iterator for Lines<&'a [u8], T>
+where
+    T: Deserialize<'a>,
+{
+    type Item = Result<T, DocumentError<serde_json::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.read.is_empty() {
+            let (line, rest) = match self.read.iter().position(|&b| b == b'\n') {
+                Some(newline) => (&self.read[..newline], &self.read[newline + 1..]),
+                None => (self.read, &[][..]),
+            };
+            self.read = rest;
+            if !is_blank(line) {
+                return Some(self.document(line));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T> FusedIterator for Lines<&'a [u8], T> where T: Deserialize<'a> {}
+
+impl<R, T> Iterator for Lines<io::BufReader<R>, T>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    type Item = Result<T, DocumentError<serde_json::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = Vec::new();
+        while !self.failed {
+            line.clear();
+            match self.read.read_until(b'\n', &mut line) {
+                Ok(0) => return None,
+                Ok(_) if is_blank(&line) => {}
+                Ok(_) => return Some(self.document(&line)),
+                Err(err) => {
+                    self.failed = true;
+                    let err = Error::new(Path::empty(), serde_json::Error::io(err));
+                    return Some(Err(DocumentError::new(self.index, err)));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Serializes the given data structure as a string of JSON.
+pub fn to_string<T>(value: &T) -> Result<String, Error<serde_json::Error>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut out = Vec::with_capacity(128);
+    to_writer(&mut out, value)?;
+    // serde_json only ever writes valid UTF-8.
+    Ok(String::from_utf8(out).unwrap_or_else(|_| unreachable!()))
+}
+
+/// Serializes the given data structure as JSON into the I/O stream.
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<(), Error<serde_json::Error>>
+where
+    W: io::Write,
+    T: ?Sized + Serialize,
+{
+    let mut ser = serde_json::Serializer::new(writer);
+    crate::serialize(value, &mut ser)
+}
+
+/// Converts the given data structure into a `serde_json::Value`.
+pub fn to_value<T>(value: &T) -> Result<Value, Error<serde_json::Error>>
+where
+    T: ?Sized + Serialize,
+{
+    crate::serialize(value, serde_json::value::Serializer)
+}
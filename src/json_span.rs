@@ -0,0 +1,275 @@
+use crate::path::{Path, Segment};
+use crate::span::Span;
+use alloc::string::String;
+
+/// Finds the value designated by `path` within the JSON document `json`.
+///
+/// Returns the span of the value's text, from its first byte up to and
+/// including its last. Use [`Span::line_column`] to turn the start of the span
+/// into a line and column for display. Returns `None` if the document does not
+/// contain a value at that path, or is not well formed along the way.
+///
+/// Map keys and struct fields select object members, sequence indices select
+/// array elements, and enum variants select the single member of an externally
+/// tagged enum (or, for a unit variant, the string itself).
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// #[derive(Deserialize)]
+/// struct Package {
+///     name: String,
+///     version: String,
+/// }
+///
+/// let j = r#"{
+///     "name": "demo",
+///     "version": 1
+/// }"#;
+///
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+/// let result: Result<Package, _> = serde_path_to_error::deserialize(jd);
+/// let err = match result {
+///     Ok(_) => panic!("expected a type error"),
+///     Err(err) => err,
+/// };
+///
+/// let span = serde_path_to_error::locate_json(j, err.path()).unwrap();
+/// assert_eq!(&j[span.start..span.end], "1");
+///
+/// let location = span.line_column(j);
+/// assert_eq!((location.line, location.column), (3, 16));
+/// ```
+pub fn locate_json(json: &str, path: &Path) -> Option<Span> {
+    locate(json, path).map(|(_key, value)| value)
+}
+
+/// Finds the object key under which the value designated by `path` appears
+/// within the JSON document `json`.
+///
+/// Returns the span of the key including its quotes. Returns `None` under the
+/// same conditions as [`locate_json`], and also when the path is empty or its
+/// last segment is a sequence index, since the value then has no key.
+pub fn locate_json_key(json: &str, path: &Path) -> Option<Span> {
+    locate(json, path).and_then(|(key, _value)| key)
+}
+
+fn locate(json: &str, path: &Path) -> Option<(Option<Span>, Span)> {
+    let mut scanner = Scanner {
+        input: json.as_bytes(),
+        pos: 0,
+    };
+    let mut key = None;
+    scanner.skip_whitespace();
+    for segment in path {
+        key = match segment {
+            Segment::Seq { index } => {
+                scanner.element(*index)?;
+                None
+            }
+            Segment::Map { key } => Some(scanner.member(key)?),
+            Segment::Enum { variant } => {
+                if scanner.peek() == Some(b'"') {
+                    // Unit variant, which is the string itself.
+                    let start = scanner.pos;
+                    let name = scanner.string()?;
+                    scanner.pos = start;
+                    if name != *variant {
+                        return None;
+                    }
+                    key
+                } else {
+                    Some(scanner.member(variant)?)
+                }
+            }
+            Segment::Unknown => return None,
+        };
+    }
+    let start = scanner.pos;
+    scanner.skip_value()?;
+    Some((key, Span::new(start, scanner.pos)))
+}
+
+// Just enough of a JSON tokenizer to walk to a given value. Values that are not
+// on the path are skipped without being validated.
+struct Scanner<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\n' | b'\r' | b'\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> Option<()> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    // Positions the scanner at the element with the given index of the array
+    // at the current position.
+    fn element(&mut self, index: usize) -> Option<()> {
+        self.eat(b'[')?;
+        for i in 0.. {
+            self.skip_whitespace();
+            if self.peek() == Some(b']') {
+                return None;
+            }
+            if i == index {
+                return Some(());
+            }
+            self.skip_value()?;
+            self.skip_whitespace();
+            self.eat(b',')?;
+        }
+        unreachable!()
+    }
+
+    // Positions the scanner at the value of the member with the given key of
+    // the object at the current position, and returns the span of the key.
+    fn member(&mut self, key: &str) -> Option<Span> {
+        self.eat(b'{')?;
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let name = self.string()?;
+            let span = Span::new(start, self.pos);
+            self.skip_whitespace();
+            self.eat(b':')?;
+            self.skip_whitespace();
+            if name == key {
+                return Some(span);
+            }
+            self.skip_value()?;
+            self.skip_whitespace();
+            self.eat(b',')?;
+        }
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'"' => self.skip_string(),
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        b'"' => self.skip_string()?,
+                        b'{' | b'[' => {
+                            depth += 1;
+                            self.pos += 1;
+                        }
+                        b'}' | b']' => {
+                            depth -= 1;
+                            self.pos += 1;
+                            if depth == 0 {
+                                return Some(());
+                            }
+                        }
+                        _ => self.pos += 1,
+                    }
+                }
+            }
+            _ => {
+                let start = self.pos;
+                while let Some(byte) = self.peek() {
+                    if let b' ' | b'\n' | b'\r' | b'\t' | b',' | b']' | b'}' = byte {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    None
+                } else {
+                    Some(())
+                }
+            }
+        }
+    }
+
+    fn skip_string(&mut self) -> Option<()> {
+        self.eat(b'"')?;
+        loop {
+            match self.peek()? {
+                b'"' => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                b'\\' => self.pos += 2,
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    // Parses the string at the current position, resolving escape sequences.
+    fn string(&mut self) -> Option<String> {
+        self.eat(b'"')?;
+        let mut string = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(byte) = self.peek() {
+                if byte == b'"' || byte == b'\\' {
+                    break;
+                }
+                self.pos += 1;
+            }
+            string.push_str(core::str::from_utf8(&self.input[start..self.pos]).ok()?);
+            self.pos += 1;
+            match self.input.get(self.pos - 1)? {
+                b'"' => return Some(string),
+                _ => string.push(self.escape()?),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Option<char> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(match byte {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\x08',
+            b'f' => '\x0c',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let first = self.hex4()?;
+                if (0xD800..0xDC00).contains(&first) {
+                    self.eat(b'\\')?;
+                    self.eat(b'u')?;
+                    let second = self.hex4()?;
+                    if !(0xDC00..0xE000).contains(&second) {
+                        return None;
+                    }
+                    let c = 0x10000 + ((first - 0xD800) << 10) + (second - 0xDC00);
+                    char::from_u32(c)?
+                } else {
+                    char::from_u32(first)?
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let digits = self.input.get(self.pos..self.pos + 4)?;
+        let digits = core::str::from_utf8(digits).ok()?;
+        let n = u32::from_str_radix(digits, 16).ok()?;
+        self.pos += 4;
+        Some(n)
+    }
+}
@@ -0,0 +1,153 @@
+use crate::content::{Content, ContentDeserializer};
+use crate::de::{deserialize_tracked, Deserializer};
+use crate::path::Path;
+use crate::{scope, Chain, Error, Track, Tracker};
+use alloc::string::{String, ToString as _};
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::marker::PhantomData;
+use serde::de::{self, Deserialize};
+
+/// Entry point for lenient deserialization.
+///
+/// Fields deserialized with [`lenient_option`] whose contents fail to
+/// deserialize are replaced by `None` instead of failing the whole input. Each
+/// replacement is reported as a [`Warning`] carrying the path of the value that
+/// was dropped.
+///
+/// Other errors are reported the same way as by [`deserialize`][crate::deserialize].
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// #[derive(Deserialize)]
+/// struct Package {
+///     name: String,
+///     #[serde(default, deserialize_with = "serde_path_to_error::lenient_option")]
+///     license: Option<License>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// enum License {
+///     MIT,
+///     Apache,
+/// }
+///
+/// let j = r#"{
+///     "name": "demo",
+///     "license": "GPL"
+/// }"#;
+///
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+/// let (package, warnings) = serde_path_to_error::deserialize_lenient::<_, Package>(jd).unwrap();
+///
+/// assert!(package.license.is_none());
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].path().to_string(), "license");
+/// ```
+pub fn deserialize_lenient<'de, D, T>(deserializer: D) -> Result<(T, Vec<Warning>), Error<D::Error>>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let track = Track::lenient();
+    let t = deserialize_tracked(PhantomData, deserializer, Tracker::new(&track))?;
+    Ok((t, track.take_warnings()))
+}
+
+/// Deserialize an optional value, replacing it with `None` if it fails to
+/// deserialize during lenient deserialization.
+///
+/// Intended for use with `#[serde(deserialize_with = "...")]` on fields of type
+/// `Option<T>`. Underneath [`deserialize_lenient`], a value that is present but
+/// does not deserialize as `T` becomes `None` and is recorded as a [`Warning`].
+/// Anywhere else this behaves the same as the ordinary `Option<T>` impl.
+///
+/// The value is buffered before it is deserialized as `T`, so malformed input
+/// such as a syntax error is still reported as an error.
+pub fn lenient_option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let content = match Option::<Content>::deserialize(deserializer)? {
+        Some(content) => content,
+        None => return Ok(None),
+    };
+
+    let mut content = Some(content);
+    let tracked = scope::with_current(|parent, outer| {
+        let content = content.take().unwrap();
+        let chain = Chain::Some { parent };
+        if !outer.is_lenient() {
+            return T::deserialize(Deserializer {
+                de: ContentDeserializer::new(content),
+                chain,
+                track: outer,
+            })
+            .map(Some);
+        }
+
+        let track = outer.child();
+        let result = {
+            let tracker = Tracker {
+                track: &track,
+                ..outer
+            };
+            let _scope = scope::enter(&chain, &tracker);
+            T::deserialize(Deserializer {
+                de: ContentDeserializer::<D::Error>::new(content),
+                chain: chain.clone(),
+                track: tracker,
+            })
+        };
+        match result {
+            Ok(t) => {
+                outer.adopt(track);
+                Ok(Some(t))
+            }
+            Err(err) => {
+                outer.warn(Warning {
+                    path: track.path(),
+                    message: err.to_string(),
+                });
+                Ok(None)
+            }
+        }
+    });
+
+    match tracked {
+        Some(result) => result,
+        None => T::deserialize(ContentDeserializer::new(content.unwrap())).map(Some),
+    }
+}
+
+/// Value that was replaced by `None` during lenient deserialization.
+#[derive(Clone, Debug)]
+pub struct Warning {
+    path: Path,
+    message: String,
+}
+
+impl Warning {
+    /// Path of the value that failed to deserialize.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Message of the error that the value failed with.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.path.is_only_unknown() {
+            write!(f, "{}: ", self.path)?;
+        }
+        f.write_str(&self.message)
+    }
+}
@@ -52,6 +52,33 @@
 //!     }
 //! }
 //! ```
+//!
+//! # Cargo features
+//!
+//! - **`json-span`** &mdash; Locates the value or key that a `Path` refers to
+//!   within JSON source text, through `locate_json()` and `locate_json_key()`.
+//!
+//! - **`miette`** &mdash; Provides `MietteReport`, which reports an error
+//!   together with its source text through `miette`. Implies `std`.
+//!
+//! - **`regex`** &mdash; Provides `Rule::regex()` for validating strings
+//!   against a regular expression. Implies `std`.
+//!
+//! - **`serde_json`** &mdash; Provides the `json` module with counterparts of
+//!   `serde_json::from_str` and friends that report the error path. Implies
+//!   `std`.
+//!
+//! - **`std`** &mdash; Makes the path of the value being deserialized available
+//!   to code running underneath the wrapper through `current_path()`, enables
+//!   lenient deserialization, and provides `flatten()` and
+//!   `internally_tagged()` for tracking paths into flattened fields and
+//!   internally tagged enums.
+//!
+//! - **`tracing`** &mdash; Emits a `tracing` event at trace level for every
+//!   node the wrapper visits, and one at warn level where the wrapper records
+//!   the path of an error, each carrying the path as a `path` field. Errors
+//!   that are recovered from, as by `Lossy` or lenient deserialization, are
+//!   reported at trace level instead.
 
 #![doc(html_root_url = "https://docs.rs/serde_path_to_error/0.1.20")]
 #![no_std]
@@ -70,25 +97,84 @@
 
 extern crate alloc;
 extern crate serde_core as serde;
+#[cfg(feature = "std")]
+extern crate std;
 
+mod content;
 mod de;
+mod diff;
+mod documents;
+mod fault;
+mod hook;
+#[cfg(feature = "serde_json")]
+pub mod json;
+#[cfg(feature = "json-span")]
+mod json_span;
+#[cfg(feature = "std")]
+mod lenient;
+mod lossy;
+#[cfg(feature = "miette")]
+mod miette;
+mod observe;
 mod path;
+mod pattern;
+mod policy;
+mod redact;
+mod render;
+#[cfg(feature = "std")]
+mod replay;
+mod scope;
 mod ser;
+mod span;
+mod untagged;
+mod validate;
+mod walk;
 mod wrap;
 
+use crate::hook::Hook;
 use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
 use core::cell::Cell;
 use core::fmt::{self, Display};
+use core::ops::Deref;
 use serde::ser::StdError;
 
-pub use crate::de::{deserialize, Deserializer};
+pub use crate::de::{
+    deserialize, deserialize_in_place, deserialize_observed, deserialize_seed,
+    deserialize_with_position, Deserializer,
+};
+pub use crate::diff::{diff, Change, Shape};
+pub use crate::documents::{documents, DocumentError, Documents};
+pub use crate::fault::Fault;
+#[cfg(feature = "json-span")]
+pub use crate::json_span::{locate_json, locate_json_key};
+#[cfg(feature = "std")]
+pub use crate::lenient::{deserialize_lenient, lenient_option, Warning};
+pub use crate::lossy::{deserialize_lossy_map, deserialize_lossy_seq, Lossy};
+#[cfg(feature = "miette")]
+pub use crate::miette::MietteReport;
+pub use crate::observe::{NodeKind, Observer, Scalar};
 pub use crate::path::{Path, Segment, Segments};
-pub use crate::ser::{serialize, Serializer};
+pub use crate::pattern::{PathPattern, PatternError};
+pub use crate::policy::Policy;
+pub use crate::redact::Redact;
+pub use crate::render::Report;
+#[cfg(feature = "std")]
+pub use crate::replay::{flatten, internally_tagged};
+#[cfg(feature = "std")]
+pub use crate::scope::current_path;
+pub use crate::ser::{serialize, serialize_with, Serializer};
+pub use crate::span::{LineColumn, Position, Span};
+pub use crate::untagged::{Untagged, UntaggedError};
+pub use crate::validate::{Rule, Rules, Violation};
+pub use crate::walk::{leaves, ScalarValue, WalkError};
 
 /// Original deserializer error together with the path at which it occurred.
 #[derive(Clone, Debug)]
 pub struct Error<E> {
     path: Path,
+    span: Option<Span>,
     original: E,
 }
 
@@ -96,6 +182,7 @@ impl<E> Error<E> {
     pub fn new(path: Path, inner: E) -> Self {
         Error {
             path,
+            span: None,
             original: inner,
         }
     }
@@ -105,6 +192,14 @@ impl<E> Error<E> {
         &self.path
     }
 
+    /// Input span of the innermost value that failed to deserialize.
+    ///
+    /// Only available when the input position was being tracked, as with
+    /// [`deserialize_with_position`] or [`Deserializer::with_position`].
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
     /// The Deserializer's underlying error that occurred.
     pub fn into_inner(self) -> E {
         self.original
@@ -116,6 +211,82 @@ impl<E> Error<E> {
     }
 }
 
+impl<E: Display> Error<E> {
+    // Reports an error to `tracing` that did not come out of the wrapper, which
+    // reports its own as it records their path.
+    #[cfg(feature = "serde_json")]
+    #[inline]
+    pub(crate) fn escape(self) -> Self {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(path = %self.path, error = %self.original, "error");
+        self
+    }
+
+    /// Converts an error from deserializing a nested document into an error of
+    /// the enclosing deserializer.
+    ///
+    /// Intended for `deserialize_with` functions that decode a value embedded
+    /// in the input, such as JSON inside of a string. When called underneath
+    /// one of this crate's entry points, the enclosing error path becomes the
+    /// path of the field being deserialized followed by this error's path, like
+    /// `payload.items[2]`. Otherwise, or without the "std" feature, the path is
+    /// only included in the error message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use serde_derive::Deserialize;
+    /// #
+    /// use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Envelope {
+    ///     #[serde(deserialize_with = "embedded_json")]
+    ///     payload: Payload,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Payload {
+    ///     items: Vec<u32>,
+    /// }
+    ///
+    /// fn embedded_json<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    /// where
+    ///     D: Deserializer<'de>,
+    ///     T: DeserializeOwned,
+    /// {
+    ///     let s = String::deserialize(deserializer)?;
+    ///     let jd = &mut serde_json::Deserializer::from_str(&s);
+    ///     serde_path_to_error::deserialize(jd).map_err(serde_path_to_error::Error::propagate)
+    /// }
+    ///
+    /// let j = r#"{"payload": "{\"items\": [1, 2, -3]}"}"#;
+    /// let jd = &mut serde_json::Deserializer::from_str(j);
+    ///
+    /// let result: Result<Envelope, _> = serde_path_to_error::deserialize(jd);
+    /// let err = match result {
+    ///     Ok(_) => panic!("expected a type error"),
+    ///     Err(err) => err,
+    /// };
+    /// // With the "std" feature, the path is "payload.items[2]". Without it, the
+    /// // path is "payload" and the message starts with "items[2]: ".
+    /// assert!(err.to_string().starts_with("payload"));
+    /// assert!(err.to_string().contains("items[2]: invalid value: integer `-3`"));
+    /// ```
+    pub fn propagate<O: serde::de::Error>(self) -> O {
+        #[cfg(feature = "std")]
+        {
+            let propagated = scope::with_current(|chain, track| {
+                track.trigger_nested(chain, &self.path, &self.original);
+            });
+            if propagated.is_some() {
+                return O::custom(self.original);
+            }
+        }
+        O::custom(self)
+    }
+}
+
 impl<E: Display> Display for Error<E> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if !self.path.is_only_unknown() {
@@ -138,6 +309,14 @@ impl<E: StdError> StdError for Error<E> {
 /// [`Deserializer`].
 pub struct Track {
     path: Cell<Option<Path>>,
+    span: Cell<Option<Span>>,
+    base: Path,
+    #[cfg(feature = "std")]
+    lenient: bool,
+    #[cfg(feature = "std")]
+    warnings: Cell<Vec<Warning>>,
+    #[cfg(feature = "tracing")]
+    recovering: bool,
 }
 
 impl Track {
@@ -145,26 +324,372 @@ impl Track {
     pub const fn new() -> Self {
         Track {
             path: Cell::new(None),
+            span: Cell::new(None),
+            base: Path::empty(),
+            #[cfg(feature = "std")]
+            lenient: false,
+            #[cfg(feature = "std")]
+            warnings: Cell::new(Vec::new()),
+            #[cfg(feature = "tracing")]
+            recovering: false,
         }
     }
 
+    /// Empty state for lenient deserialization, in which fields deserialized
+    /// with [`lenient_option`] become `None` instead of failing.
+    ///
+    /// You don't need this if you are using
+    /// `serde_path_to_error::deserialize_lenient`.
+    #[cfg(feature = "std")]
+    pub const fn lenient() -> Self {
+        Track {
+            path: Cell::new(None),
+            span: Cell::new(None),
+            base: Path::empty(),
+            lenient: true,
+            warnings: Cell::new(Vec::new()),
+            #[cfg(feature = "tracing")]
+            recovering: false,
+        }
+    }
+
+    /// Removes and returns the warnings recorded so far during lenient
+    /// deserialization.
+    #[cfg(feature = "std")]
+    pub fn take_warnings(&self) -> Vec<Warning> {
+        self.warnings.take()
+    }
+
+    /// Gets the input span of the innermost value that failed to deserialize.
+    /// Returns `None` if no error has occurred or no position is attached.
+    pub fn span(&self) -> Option<Span> {
+        self.span.get()
+    }
+
     /// Gets path at which the error occurred. Only meaningful after we know
     /// that an error has occurred. Returns an empty path otherwise.
     pub fn path(self) -> Path {
         self.path.into_inner().unwrap_or_else(Path::empty)
     }
 
+    /// Removes and returns the path at which the error occurred, leaving the
+    /// state ready to track another error. Returns an empty path if no error
+    /// has occurred.
+    pub fn take_path(&self) -> Path {
+        self.path.take().unwrap_or_else(Path::empty)
+    }
+
+    /// Whether an error has occurred since this state was created or last
+    /// reset.
+    pub fn has_error(&self) -> bool {
+        let path = self.path.take();
+        let has_error = path.is_some();
+        self.path.set(path);
+        has_error
+    }
+
+    /// Forgets the error path, span and warnings recorded so far, so that the
+    /// same state can be reused to deserialize another document. The base path
+    /// is kept.
+    pub fn reset(&self) {
+        self.path.set(None);
+        self.span.set(None);
+        #[cfg(feature = "std")]
+        self.warnings.set(Vec::new());
+    }
+
+    /// Sets the path that all paths recorded from now on are relative to, such
+    /// as the index of the document within a stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use serde_derive::Deserialize;
+    /// #
+    /// use serde::Deserialize;
+    /// use serde_path_to_error::{Path, Segment, Track};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Record {
+    ///     id: u64,
+    /// }
+    ///
+    /// let ndjson = "{\"id\": 1}\n{\"id\": 2}\n{\"id\": \"3\"}\n";
+    ///
+    /// let mut track = Track::new();
+    /// let mut failures = Vec::new();
+    /// for (i, line) in ndjson.lines().enumerate() {
+    ///     track.set_base(Path::from_iter([Segment::Seq { index: i }]));
+    ///     let jd = &mut serde_json::Deserializer::from_str(line);
+    ///     let pd = serde_path_to_error::Deserializer::new(jd, &mut track);
+    ///     if Record::deserialize(pd).is_err() {
+    ///         failures.push(track.take_path().to_string());
+    ///     }
+    ///     track.reset();
+    /// }
+    ///
+    /// assert_eq!(failures, ["[2].id"]);
+    /// ```
+    pub fn set_base(&mut self, base: Path) {
+        self.base = base;
+    }
+
     #[inline]
-    fn trigger<E>(&self, chain: &Chain, err: E) -> E {
-        self.trigger_impl(chain);
+    fn trigger<E: Display>(&self, chain: &Chain, err: E) -> E {
+        self.trigger_impl(chain, &err);
         err
     }
 
-    fn trigger_impl(&self, chain: &Chain) {
-        self.path.set(Some(match self.path.take() {
+    // Empty state for replaying a buffered value whose errors are recovered
+    // from, as by `Lossy` or `Untagged`, when there is no enclosing wrapper.
+    fn recovering() -> Track {
+        Track {
+            #[cfg(feature = "tracing")]
+            recovering: true,
+            ..Track::new()
+        }
+    }
+
+    // Empty state under the same base path, and just as lenient, as this one.
+    // Warnings recorded in it are passed on with `adopt` once whatever it was
+    // used for has succeeded, and its errors are recovered from or else
+    // reported again by the enclosing wrapper.
+    #[cfg(feature = "std")]
+    fn child(&self) -> Track {
+        Track {
+            path: Cell::new(None),
+            span: Cell::new(None),
+            base: self.base.clone(),
+            lenient: self.lenient,
+            warnings: Cell::new(Vec::new()),
+            #[cfg(feature = "tracing")]
+            recovering: true,
+        }
+    }
+
+    // Takes over the warnings of a child.
+    #[cfg(feature = "std")]
+    fn adopt(&self, child: Track) {
+        for warning in child.warnings.into_inner() {
+            self.warn(warning);
+        }
+    }
+
+    fn path_of(&self, chain: &Chain) -> Path {
+        Path::from_chain(&self.base, chain)
+    }
+
+    #[cfg(feature = "std")]
+    fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    #[cfg(feature = "std")]
+    fn warn(&self, warning: Warning) {
+        let mut warnings = self.warnings.take();
+        warnings.push(warning);
+        self.warnings.set(warnings);
+    }
+
+    // Records an error whose path continues past `chain` with the given path
+    // from within a nested document.
+    #[cfg(feature = "std")]
+    fn trigger_nested(&self, chain: &Chain, inner: &Path, err: &dyn Display) {
+        self.record_path(|| self.path_of(chain).join(inner), err);
+    }
+
+    fn trigger_impl(&self, chain: &Chain, err: &dyn Display) {
+        self.record_path(|| self.path_of(chain), err);
+    }
+
+    // Only the innermost path is kept, so this is also where an error is
+    // reported to `tracing`: at warn level, unless this state belongs to a
+    // value whose errors are recovered from.
+    fn record_path(&self, path: impl FnOnce() -> Path, err: &dyn Display) {
+        let path = match self.path.take() {
             Some(already_set) => already_set,
-            None => Path::from_chain(chain),
-        }));
+            None => {
+                let path = path();
+                #[cfg(feature = "tracing")]
+                if self.recovering {
+                    tracing::trace!(path = %path, error = %err, "recovered");
+                } else {
+                    tracing::warn!(path = %path, error = %err, "error");
+                }
+                #[cfg(not(feature = "tracing"))]
+                let _ = err;
+                path
+            }
+        };
+        self.path.set(Some(path));
+    }
+}
+
+// A `Track` together with the observer, position and hook attached to the
+// wrapper that is using it. Copied into every nested wrapper; the shared state
+// lives in the `Track`.
+#[derive(Copy, Clone)]
+struct Tracker<'a> {
+    track: &'a Track,
+    observer: Option<&'a dyn Observer>,
+    position: Option<&'a dyn Position>,
+    hook: Option<&'a dyn Hook>,
+}
+
+impl<'a> Deref for Tracker<'a> {
+    type Target = Track;
+
+    fn deref(&self) -> &Track {
+        self.track
+    }
+}
+
+impl<'a> Tracker<'a> {
+    fn new(track: &'a Track) -> Self {
+        Tracker {
+            track,
+            observer: None,
+            position: None,
+            hook: None,
+        }
+    }
+
+    fn with_observer(self, observer: &'a dyn Observer) -> Self {
+        Tracker {
+            observer: Some(observer),
+            ..self
+        }
+    }
+
+    fn with_position(self, position: &'a dyn Position) -> Self {
+        Tracker {
+            position: Some(position),
+            ..self
+        }
+    }
+
+    // Consults the given hook for every value serialized by the `Serializer`
+    // wrapper.
+    fn with_hook(self, hook: &'a dyn Hook) -> Self {
+        Tracker {
+            hook: Some(hook),
+            ..self
+        }
+    }
+
+    // Like `trigger`, for an error out of a value that started at input offset
+    // `start`.
+    #[inline]
+    fn trigger_node<E: Display>(&self, chain: &Chain, start: Option<usize>, err: E) -> E {
+        self.trigger_impl(chain, &err);
+        if let Some(start) = start {
+            self.record_span(start);
+        }
+        err
+    }
+
+    #[inline]
+    fn offset(&self) -> Option<usize> {
+        self.position.map(Position::offset)
+    }
+
+    #[inline]
+    fn enter(&self, chain: &Chain, kind: NodeKind) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(path = %self.path_of(chain), kind = ?kind, "enter");
+        if let Some(observer) = self.observer {
+            observer.enter(&self.path_of(chain), kind);
+        }
+    }
+
+    #[inline]
+    fn leaf(&self, chain: &Chain, scalar: Scalar) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(path = %self.path_of(chain), "leaf");
+        if let Some(observer) = self.observer {
+            observer.leaf(&self.path_of(chain), scalar);
+        }
+    }
+
+    #[inline]
+    fn exit(&self, chain: &Chain) {
+        if let Some(observer) = self.observer {
+            observer.exit(&self.path_of(chain));
+        }
+    }
+
+    #[inline]
+    fn serialize_node<E: serde::ser::Error>(&self, chain: &Chain) -> Result<(), E> {
+        self.check_node(chain)
+            .map_err(|message| self.trigger(chain, E::custom(message)))
+    }
+
+    #[inline]
+    fn deserialize_node<E: serde::de::Error>(&self, chain: &Chain) -> Result<(), E> {
+        self.check_node(chain)
+            .map_err(|message| self.trigger(chain, E::custom(message)))
+    }
+
+    fn check_node(&self, chain: &Chain) -> Result<(), String> {
+        match self.hook {
+            Some(hook) => hook.node(&self.path_of(chain)),
+            None => Ok(()),
+        }
+    }
+
+    #[inline]
+    fn serialize_enter(&self, chain: &Chain, kind: NodeKind) {
+        if let Some(hook) = self.hook {
+            hook.enter(&self.path_of(chain), kind);
+        }
+    }
+
+    #[inline]
+    fn serialize_leaf<E: serde::ser::Error>(&self, chain: &Chain, scalar: Scalar) -> Result<(), E> {
+        if let Some(hook) = self.hook {
+            let path = self.path_of(chain);
+            hook.leaf(&path, scalar);
+            if let Err(message) = hook.check(&path, scalar) {
+                return Err(self.trigger(chain, E::custom(message)));
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_key<E: serde::ser::Error>(&self, chain: &Chain, string: bool) -> Result<(), E> {
+        if let Some(hook) = self.hook {
+            if let Err(message) = hook.check_key(&self.path_of(chain), string) {
+                return Err(self.trigger(chain, E::custom(message)));
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_exit<E: serde::ser::Error>(&self, chain: &Chain) -> Result<(), E> {
+        if let Some(hook) = self.hook {
+            let path = self.path_of(chain);
+            hook.exit(&path);
+            if let Err(message) = hook.check_exit(&path) {
+                return Err(self.trigger(chain, E::custom(message)));
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_variant(&self, chain: &Chain, variant: &str) {
+        if let Some(hook) = self.hook {
+            hook.variant(&self.path_of(chain), variant);
+        }
+    }
+
+    fn record_span(&self, start: usize) {
+        if self.span.get().is_none() {
+            let end = self.offset().unwrap_or(start);
+            self.span.set(Some(Span::new(start, end.max(start))));
+        }
     }
 }
 
@@ -0,0 +1,519 @@
+use crate::content::{Content, ContentDeserializer};
+use crate::de::{deserialize_tracked, Deserializer};
+use crate::scope;
+use crate::{Chain, Error, Span, Track, Tracker};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString as _};
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::marker::PhantomData;
+use serde::de::{self, Deserialize, DeserializeSeed, Visitor};
+
+/// Deserialize a sequence, skipping over elements that fail to deserialize.
+///
+/// Each element is buffered and then deserialized on its own, so a type error
+/// in one element does not prevent the remaining elements from being read. The
+/// successfully deserialized elements are returned together with one error per
+/// failed element, each carrying the path of that failure.
+///
+/// An error that leaves the input unreadable, such as a syntax error or input
+/// that is not a sequence at all, still aborts the whole operation.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// #[derive(Deserialize)]
+/// struct Row {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let j = r#"[
+///     {"id": 1, "name": "first"},
+///     {"id": "two", "name": "second"},
+///     {"id": 3, "name": "third"},
+///     {"id": 4}
+/// ]"#;
+///
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+/// let (rows, errors) = serde_path_to_error::deserialize_lossy_seq::<_, Row>(jd).unwrap();
+///
+/// assert_eq!(rows.len(), 2);
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].path().to_string(), "[1].id");
+/// assert_eq!(errors[1].path().to_string(), "[3]");
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn deserialize_lossy_seq<'de, D, T>(
+    deserializer: D,
+) -> Result<(Vec<T>, Vec<Error<D::Error>>), Error<D::Error>>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let track = Track::new();
+    let visitor = LossySeqVisitor::<T, D::Error>::new();
+    deserialize_tracked(visitor, deserializer, Tracker::new(&track))
+}
+
+/// Deserialize a map, skipping over entries that fail to deserialize.
+///
+/// This is the map counterpart of [`deserialize_lossy_seq`]. An entry whose key
+/// or value fails to deserialize is left out of the result and reported in the
+/// returned list of errors. Entries are returned in input order.
+#[allow(clippy::type_complexity)]
+pub fn deserialize_lossy_map<'de, D, K, V>(
+    deserializer: D,
+) -> Result<(Vec<(K, V)>, Vec<Error<D::Error>>), Error<D::Error>>
+where
+    D: de::Deserializer<'de>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    let track = Track::new();
+    let visitor = LossyMapVisitor::<K, V, D::Error>::new();
+    deserialize_tracked(visitor, deserializer, Tracker::new(&track))
+}
+
+/// A collection deserialized the way [`deserialize_lossy_seq`] and
+/// [`deserialize_lossy_map`] do, for use as a field inside of a larger value.
+///
+/// `Lossy<Vec<T>>` deserializes from a sequence and `Lossy<BTreeMap<K, V>>` from
+/// a map. Elements that fail to deserialize are left out of the collection and
+/// their errors are kept alongside it, so the rest of the containing value
+/// still deserializes.
+///
+/// When deserialized underneath one of this crate's entry points, the errors
+/// carry the full path from the root of the input, as well as the span of the
+/// failed element if the entry point tracks positions. Elsewhere, paths are
+/// relative to the collection, as explained under [`current_path`]. Only the
+/// message of the original error is kept, since the format's error type is not
+/// known here.
+///
+/// [`current_path`]: crate::current_path
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// use serde_path_to_error::Lossy;
+///
+/// #[derive(Deserialize)]
+/// struct Upload {
+///     name: String,
+///     rows: Lossy<Vec<Row>>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     id: u32,
+/// }
+///
+/// let j = r#"{"name": "batch", "rows": [{"id": 1}, {"id": "two"}, {"id": 3}]}"#;
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+/// let upload: Upload = serde_path_to_error::deserialize(jd).unwrap();
+///
+/// assert_eq!(upload.rows.len(), 2);
+/// assert_eq!(upload.rows.errors().len(), 1);
+/// # #[cfg(feature = "std")]
+/// assert_eq!(upload.rows.errors()[0].path().to_string(), "rows[1].id");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Lossy<T> {
+    value: T,
+    errors: Vec<Error<String>>,
+}
+
+impl<T> Lossy<T> {
+    /// The successfully deserialized elements.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// One error per element that failed to deserialize, in input order.
+    pub fn errors(&self) -> &[Error<String>] {
+        &self.errors
+    }
+
+    /// Take ownership of the successfully deserialized elements.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Take ownership of the elements and the errors.
+    pub fn into_parts(self) -> (T, Vec<Error<String>>) {
+        (self.value, self.errors)
+    }
+}
+
+impl<T> core::ops::Deref for Lossy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Lossy<Vec<T>>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let visitor = LossySeqVisitor::<T, Message>::new();
+        let (value, errors) = deserializer.deserialize_seq(visitor)?;
+        Ok(Lossy {
+            value,
+            errors: errors.into_iter().map(Message::unwrap).collect(),
+        })
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for Lossy<BTreeMap<K, V>>
+where
+    K: Ord + Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let visitor = LossyMapVisitor::<K, V, Message>::new();
+        let (entries, errors) = deserializer.deserialize_map(visitor)?;
+        Ok(Lossy {
+            value: entries.into_iter().collect(),
+            errors: errors.into_iter().map(Message::unwrap).collect(),
+        })
+    }
+}
+
+// Error type that elements of a `Lossy` are replayed with. Only the message
+// survives into `Lossy::errors`.
+#[derive(Debug)]
+struct Message(String);
+
+impl Message {
+    fn unwrap(err: Error<Message>) -> Error<String> {
+        Error {
+            path: err.path,
+            span: err.span,
+            original: err.original.0,
+        }
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl serde::de::StdError for Message {}
+
+impl de::Error for Message {
+    fn custom<T: Display>(msg: T) -> Self {
+        Message(msg.to_string())
+    }
+}
+
+// A single element, key or value, buffered so that a failure to deserialize
+// it does not leave the input half read. The span covers the buffered input
+// when positions are being tracked.
+#[derive(Clone)]
+pub(crate) struct Buffered<'de> {
+    content: Content<'de>,
+    span: Option<Span>,
+}
+
+// Seed that buffers a value. The start offset is taken by the caller before
+// asking for the value, as the format may already have looked ahead at the
+// value's first byte by the time the seed runs.
+pub(crate) struct Buffer {
+    start: Option<usize>,
+}
+
+impl Buffer {
+    pub(crate) fn start() -> Self {
+        Buffer { start: offset() }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Buffer {
+    type Value = Buffered<'de>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let content = Content::deserialize(deserializer)?;
+        let span = match (self.start, offset()) {
+            (Some(start), Some(end)) => Some(Span::new(start, end.max(start))),
+            _ => None,
+        };
+        Ok(Buffered { content, span })
+    }
+}
+
+impl<'de> Buffered<'de> {
+    // Replay into T at the location currently being deserialized, so that the
+    // error path continues on from the enclosing document. Falls back to the
+    // given chain underneath an empty path when there is no such location.
+    fn replay<T, E>(self, fallback: &Chain) -> Result<T, Error<E>>
+    where
+        T: Deserialize<'de>,
+        E: de::Error,
+    {
+        let mut buffered = Some(self);
+        #[cfg(feature = "std")]
+        {
+            let current = scope::with_current(|chain, outer| {
+                // Everything in the buffered value has already been shown to
+                // the observer and hook while buffering, so the replay is not.
+                let track = outer.child();
+                let buffered = buffered.take().unwrap();
+                let result = buffered.replay_at(chain, Tracker::new(&track));
+                if result.is_ok() {
+                    outer.adopt(track);
+                }
+                result
+            });
+            if let Some(result) = current {
+                return result;
+            }
+        }
+        let track = Track::recovering();
+        let buffered = buffered.take().unwrap();
+        buffered.replay_at(fallback, Tracker::new(&track))
+    }
+
+    pub(crate) fn replay_at<T, E>(self, chain: &Chain, track: Tracker) -> Result<T, Error<E>>
+    where
+        T: Deserialize<'de>,
+        E: de::Error,
+    {
+        let result = {
+            let _scope = scope::enter(chain, &track);
+            T::deserialize(Deserializer {
+                de: ContentDeserializer::new(self.content),
+                chain: chain.clone(),
+                track,
+            })
+        };
+        match result {
+            Ok(t) => Ok(t),
+            Err(err) => Err(Error {
+                span: self.span,
+                path: track.take_path(),
+                original: err,
+            }),
+        }
+    }
+}
+
+// Byte offset reached in the input of the document currently being
+// deserialized, if positions are being tracked.
+fn offset() -> Option<usize> {
+    #[cfg(feature = "std")]
+    {
+        scope::with_current(|_chain, track| track.offset()).flatten()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        None
+    }
+}
+
+// Seed for one element of a lossy sequence. An error that leaves the input
+// unreadable is returned as the format's error; any other error is returned
+// as the seed's value.
+struct Element<'c, T, E> {
+    buffer: Buffer,
+    fallback: &'c Chain<'c>,
+    marker: PhantomData<fn() -> (T, E)>,
+}
+
+impl<'de, T, E> DeserializeSeed<'de> for Element<'_, T, E>
+where
+    T: Deserialize<'de>,
+    E: de::Error,
+{
+    type Value = Result<T, Error<E>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let buffered = self.buffer.deserialize(deserializer)?;
+        Ok(buffered.replay(self.fallback))
+    }
+}
+
+// Seed for the value of one entry of a lossy map, which also replays the
+// buffered key so that both are replayed at the entry's location.
+#[allow(clippy::type_complexity)]
+struct Entry<'c, 'de, K, V, E> {
+    key: Buffered<'de>,
+    buffer: Buffer,
+    fallback: &'c Chain<'c>,
+    marker: PhantomData<fn() -> (K, V, E)>,
+}
+
+impl<'de, K, V, E> DeserializeSeed<'de> for Entry<'_, 'de, K, V, E>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+    E: de::Error,
+{
+    type Value = Result<(K, V), Error<E>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let value = self.buffer.deserialize(deserializer)?;
+        let key = match self.key.replay(self.fallback) {
+            Ok(key) => key,
+            Err(err) => return Ok(Err(err)),
+        };
+        Ok(value.replay(self.fallback).map(|value| (key, value)))
+    }
+}
+
+struct LossySeqVisitor<T, E> {
+    marker: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T, E> LossySeqVisitor<T, E> {
+    fn new() -> Self {
+        LossySeqVisitor {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T, E> DeserializeSeed<'de> for LossySeqVisitor<T, E>
+where
+    T: Deserialize<'de>,
+    E: de::Error,
+{
+    type Value = (Vec<T>, Vec<Error<E>>);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T, E> Visitor<'de> for LossySeqVisitor<T, E>
+where
+    T: Deserialize<'de>,
+    E: de::Error,
+{
+    type Value = (Vec<T>, Vec<Error<E>>);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+        let mut index = 0;
+        loop {
+            let fallback = Chain::Seq {
+                parent: &Chain::Root,
+                index,
+            };
+            let element = Element::<T, E> {
+                buffer: Buffer::start(),
+                fallback: &fallback,
+                marker: PhantomData,
+            };
+            match seq.next_element_seed(element)? {
+                Some(Ok(value)) => values.push(value),
+                Some(Err(err)) => errors.push(err),
+                None => break,
+            }
+            index += 1;
+        }
+        Ok((values, errors))
+    }
+}
+
+#[allow(clippy::type_complexity)]
+struct LossyMapVisitor<K, V, E> {
+    marker: PhantomData<fn() -> (K, V, E)>,
+}
+
+impl<K, V, E> LossyMapVisitor<K, V, E> {
+    fn new() -> Self {
+        LossyMapVisitor {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, K, V, E> DeserializeSeed<'de> for LossyMapVisitor<K, V, E>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+    E: de::Error,
+{
+    type Value = (Vec<(K, V)>, Vec<Error<E>>);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, K, V, E> Visitor<'de> for LossyMapVisitor<K, V, E>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+    E: de::Error,
+{
+    type Value = (Vec<(K, V)>, Vec<Error<E>>);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        while let Some(key) = map.next_key_seed(Buffer::start())? {
+            let parent = &Chain::Root;
+            let fallback = match key.content.as_key() {
+                Some(key) => Chain::Map { parent, key },
+                None => Chain::NonStringKey { parent },
+            };
+            let entry = Entry::<K, V, E> {
+                key,
+                buffer: Buffer::start(),
+                fallback: &fallback,
+                marker: PhantomData,
+            };
+            match map.next_value_seed(entry)? {
+                Ok(entry) => entries.push(entry),
+                Err(err) => errors.push(err),
+            }
+        }
+        Ok((entries, errors))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::span::Span;
+use crate::Error;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString as _};
+use core::fmt::{self, Display};
+use core::iter;
+use miette::{LabeledSpan, SourceCode, SourceSpan};
+use std::error::Error as StdError;
+
+/// [`Error`] together with the source text it came from, for reporting through
+/// [`miette`].
+///
+/// The error's own message, which starts with its path, is the message of the
+/// report. The path is also used as the label of the source span, and the
+/// inner error's message, without the path, as the help text. The span is the error's own [span][Error::span] unless one is given using
+/// [`with_span`][MietteReport::with_span]. Without a span, no source snippet is
+/// shown.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// #[derive(Deserialize, Debug)]
+/// struct Package {
+///     name: String,
+/// }
+///
+/// fn parse(j: &str) -> miette::Result<Package> {
+///     let jd = &mut serde_json::Deserializer::from_str(j);
+///     serde_path_to_error::deserialize(jd).map_err(|err| {
+///         let report = serde_path_to_error::MietteReport::new(err, j);
+///         miette::Report::new(report)
+///     })
+/// }
+///
+/// let report = parse(r#"{"name": 1}"#).unwrap_err();
+/// assert_eq!(
+///     report.to_string(),
+///     "name: invalid type: integer `1`, expected a string at line 1 column 10",
+/// );
+/// assert_eq!(
+///     report.help().unwrap().to_string(),
+///     "invalid type: integer `1`, expected a string at line 1 column 10",
+/// );
+/// ```
+#[derive(Debug)]
+pub struct MietteReport<E> {
+    error: Error<E>,
+    source: String,
+    span: Option<Span>,
+}
+
+impl<E> MietteReport<E> {
+    /// Report of `error`, showing a snippet of `source`, the text that was
+    /// being deserialized when the error occurred.
+    pub fn new(error: Error<E>, source: impl Into<String>) -> Self {
+        let span = error.span();
+        MietteReport {
+            error,
+            source: source.into(),
+            span,
+        }
+    }
+
+    /// Labels the given span of the source instead of the error's own.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Reference to the underlying error.
+    pub fn error(&self) -> &Error<E> {
+        &self.error
+    }
+
+    /// The underlying error.
+    pub fn into_error(self) -> Error<E> {
+        self.error
+    }
+}
+
+impl<E: Display> Display for MietteReport<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl<E: StdError> StdError for MietteReport<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+impl<E: StdError> miette::Diagnostic for MietteReport<E> {
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(&self.error.original))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.source)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let span = self.span?.trim(&self.source);
+        let label = if self.error.path.is_only_unknown() {
+            None
+        } else {
+            Some(self.error.path.to_string())
+        };
+        Some(Box::new(iter::once(LabeledSpan::new_with_span(
+            label, span,
+        ))))
+    }
+}
+
+impl From<Span> for SourceSpan {
+    fn from(span: Span) -> Self {
+        SourceSpan::from(span.start..span.end)
+    }
+}
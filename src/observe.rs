@@ -0,0 +1,102 @@
+use crate::path::Path;
+
+/// Receives an event for every node visited by the [`Deserializer`] wrapper.
+///
+/// Pass an observer to [`deserialize_observed`], or attach one to a
+/// [`Deserializer`] using [`Deserializer::with_observer`]. Compound values
+/// produce an `enter` and a matching `exit` with their own path, with the
+/// events of their elements in between. Everything else produces a single
+/// `leaf`, except a scalar the deserializer rejects without producing a value,
+/// which is skipped (see [`leaf`](Observer::leaf)).
+///
+/// Methods take `&self` so that the same observer can be consulted from any
+/// depth of nesting; use a `Cell` or `RefCell` to accumulate state.
+///
+/// [`deserialize_observed`]: crate::deserialize_observed
+/// [`Deserializer`]: crate::Deserializer
+/// [`Deserializer::with_observer`]: crate::Deserializer::with_observer
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// use serde::Deserialize;
+/// use serde_path_to_error::{Observer, Path, Scalar};
+/// use std::cell::RefCell;
+///
+/// #[derive(Deserialize)]
+/// struct Package {
+///     name: String,
+///     keywords: Vec<String>,
+/// }
+///
+/// #[derive(Default)]
+/// struct Leaves(RefCell<Vec<String>>);
+///
+/// impl Observer for Leaves {
+///     fn leaf(&self, path: &Path, _scalar: Scalar) {
+///         self.0.borrow_mut().push(path.to_string());
+///     }
+/// }
+///
+/// let j = r#"{"name": "demo", "keywords": ["serde", "error"]}"#;
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+///
+/// let leaves = Leaves::default();
+/// let mut track = serde_path_to_error::Track::new();
+/// let pd = serde_path_to_error::Deserializer::new(jd, &mut track).with_observer(&leaves);
+/// Package::deserialize(pd).unwrap();
+///
+/// assert_eq!(*leaves.0.borrow(), ["name", "keywords[0]", "keywords[1]"]);
+/// ```
+pub trait Observer {
+    /// Called before the contents of a sequence, map or enum are visited.
+    fn enter(&self, path: &Path, kind: NodeKind) {
+        let _ = (path, kind);
+    }
+
+    /// Called for every scalar value that the deserializer hands to the
+    /// value's `Deserialize` impl, whether or not it is accepted there.
+    ///
+    /// A scalar that the deserializer rejects on its own is skipped, since its
+    /// value is never seen. For example `serde_json` fails without producing a
+    /// value when it finds a string where the `Deserialize` impl asked for a
+    /// number, so no `leaf` is called for it, only `enter` and `exit` of the
+    /// values around it.
+    fn leaf(&self, path: &Path, scalar: Scalar) {
+        let _ = (path, scalar);
+    }
+
+    /// Called after the contents of a sequence, map or enum have been visited,
+    /// whether or not they deserialized successfully.
+    fn exit(&self, path: &Path) {
+        let _ = path;
+    }
+}
+
+/// Kind of compound value entered by the deserializer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NodeKind {
+    Seq,
+    Map,
+    Enum,
+}
+
+/// Scalar value seen by the deserializer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Scalar<'a> {
+    Bool(bool),
+    I64(i64),
+    I128(i128),
+    U64(u64),
+    U128(u128),
+    F64(f64),
+    Char(char),
+    Str(&'a str),
+    Bytes(&'a [u8]),
+    Unit,
+    None,
+}
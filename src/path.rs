@@ -33,6 +33,14 @@ impl Path {
     }
 }
 
+impl FromIterator<Segment> for Path {
+    fn from_iter<I: IntoIterator<Item = Segment>>(segments: I) -> Self {
+        Path {
+            segments: segments.into_iter().collect(),
+        }
+    }
+}
+
 impl<'a> IntoIterator for &'a Path {
     type Item = &'a Segment;
     type IntoIter = Segments<'a>;
@@ -91,13 +99,107 @@ impl Display for Path {
 }
 
 impl Path {
-    pub(crate) fn empty() -> Self {
+    /// JSON Pointer ([RFC 6901]) designating the same value, like
+    /// `/dependencies/serde/typo1`, as accepted by `serde_json::Value::pointer`.
+    ///
+    /// An enum variant becomes the key of an externally tagged enum. Returns
+    /// `None` if the path contains an unknown segment.
+    ///
+    /// [RFC 6901]: https://tools.ietf.org/html/rfc6901
+    pub fn to_json_pointer(&self) -> Option<String> {
+        let mut pointer = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Seq { index } => {
+                    pointer.push('/');
+                    pointer.push_str(itoa::Buffer::new().format(*index));
+                }
+                Segment::Map { key } | Segment::Enum { variant: key } => {
+                    pointer.push('/');
+                    pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                }
+                Segment::Unknown => return None,
+            }
+        }
+        Some(pointer)
+    }
+
+    /// Looks up the value designated by this path within a JSON value tree.
+    ///
+    /// Map keys and struct fields select object members, sequence indices
+    /// select array elements, and enum variants select the single member of an
+    /// externally tagged enum (or, for a unit variant, the string itself).
+    /// Returns `None` if there is no such value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use serde_derive::Deserialize;
+    /// #
+    /// use serde_json::json;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Package {
+    ///     keywords: Vec<String>,
+    /// }
+    ///
+    /// let value = json!({"keywords": ["serde", 1]});
+    /// let result: Result<Package, _> = serde_path_to_error::json::from_value(value.clone());
+    /// let err = match result {
+    ///     Ok(_) => panic!("expected a type error"),
+    ///     Err(err) => err,
+    /// };
+    /// assert_eq!(err.path().get(&value), Some(&json!(1)));
+    /// ```
+    #[cfg(feature = "serde_json")]
+    pub fn get<'a>(&self, mut value: &'a serde_json::Value) -> Option<&'a serde_json::Value> {
+        use serde_json::Value;
+        for segment in &self.segments {
+            value = match (segment, value) {
+                (Segment::Seq { index }, Value::Array(array)) => array.get(*index)?,
+                (Segment::Map { key }, Value::Object(object)) => object.get(key)?,
+                (Segment::Enum { variant }, Value::Object(object)) => object.get(variant)?,
+                (Segment::Enum { variant }, Value::String(string)) if string == variant => value,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// Looks up the value designated by this path within a JSON value tree,
+    /// for modification. See [`get`][Path::get].
+    #[cfg(feature = "serde_json")]
+    pub fn get_mut<'a>(
+        &self,
+        mut value: &'a mut serde_json::Value,
+    ) -> Option<&'a mut serde_json::Value> {
+        use serde_json::Value;
+        for segment in &self.segments {
+            value = match (segment, value) {
+                (Segment::Seq { index }, Value::Array(array)) => array.get_mut(*index)?,
+                (Segment::Map { key }, Value::Object(object)) => object.get_mut(key)?,
+                (Segment::Enum { variant }, Value::Object(object)) => object.get_mut(variant)?,
+                (Segment::Enum { variant }, value @ Value::String(_)) => {
+                    if value.as_str() != Some(variant) {
+                        return None;
+                    }
+                    value
+                }
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+}
+
+impl Path {
+    pub(crate) const fn empty() -> Self {
         Path {
             segments: Vec::new(),
         }
     }
 
-    pub(crate) fn from_chain(mut chain: &Chain) -> Self {
+    pub(crate) fn from_chain(base: &Path, mut chain: &Chain) -> Self {
         let mut segments = Vec::new();
         loop {
             match chain {
@@ -134,10 +236,24 @@ impl Path {
                 }
             }
         }
+        segments.extend(base.segments.iter().rev().cloned());
         segments.reverse();
         Path { segments }
     }
 
+    #[cfg(feature = "std")]
+    pub(crate) fn join(mut self, other: &Path) -> Self {
+        self.segments.extend(other.segments.iter().cloned());
+        self
+    }
+
+    // The path with its first `n` segments removed.
+    pub(crate) fn skip(&self, n: usize) -> Self {
+        Path {
+            segments: self.segments.iter().skip(n).cloned().collect(),
+        }
+    }
+
     pub(crate) fn is_only_unknown(&self) -> bool {
         self.segments.iter().all(Segment::is_unknown)
     }
@@ -0,0 +1,253 @@
+use crate::path::{Path, Segment};
+use alloc::borrow::ToOwned as _;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::str::FromStr;
+use serde::ser::StdError;
+
+/// Pattern over paths, like `dependencies.*.version` or `items[*].id`.
+///
+/// A pattern is written like the [`Display`] representation of a [`Path`],
+/// with wildcards allowed in place of segments:
+///
+/// - `name` matches a map key, struct field or enum variant of that name;
+/// - `[3]` matches the element at index 3 of a sequence;
+/// - `[*]` matches any element of a sequence;
+/// - `[1..4]`, `[1..=3]`, `[2..]` and `[..5]` match the elements of a sequence
+///   whose index is in the range;
+/// - `*` matches any single segment;
+/// - `**` matches any number of segments, including none.
+///
+/// Names and wildcards are separated by periods, while indices follow the
+/// preceding segment directly. Matching is done on the segments of the path,
+/// so `[*]` does not match a map key that happens to look like a number.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// use serde_path_to_error::PathPattern;
+/// use std::collections::BTreeMap as Map;
+///
+/// #[derive(Deserialize)]
+/// struct Package {
+///     name: String,
+///     dependencies: Map<String, Dependency>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Dependency {
+///     version: String,
+/// }
+///
+/// let j = r#"{"name": "demo", "dependencies": {"serde": {"version": 1}}}"#;
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+///
+/// let result: Result<Package, _> = serde_path_to_error::deserialize(jd);
+/// let err = match result {
+///     Ok(_) => panic!("expected a type error"),
+///     Err(err) => err,
+/// };
+///
+/// let dependency_errors: PathPattern = "dependencies.**".parse().unwrap();
+/// assert!(dependency_errors.matches(err.path()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct PathPattern {
+    parts: Vec<Part>,
+}
+
+#[derive(Clone, Debug)]
+enum Part {
+    Key(String),
+    Index(Range),
+    Any,
+    AnyDepth,
+}
+
+// Indices from `start` up to but excluding `end`, or without an upper bound.
+#[derive(Copy, Clone, Debug)]
+struct Range {
+    start: usize,
+    end: Option<usize>,
+}
+
+impl PathPattern {
+    /// Whether the given path matches the pattern in its entirety.
+    pub fn matches(&self, path: &Path) -> bool {
+        let segments: Vec<&Segment> = path.iter().collect();
+        matches(&self.parts, &segments)
+    }
+}
+
+fn matches(parts: &[Part], segments: &[&Segment]) -> bool {
+    let (part, rest) = match parts.split_first() {
+        Some(split) => split,
+        None => return segments.is_empty(),
+    };
+    if let Part::AnyDepth = part {
+        return (0..=segments.len()).any(|skip| matches(rest, &segments[skip..]));
+    }
+    match segments.split_first() {
+        Some((segment, segments)) => part.matches(segment) && matches(rest, segments),
+        None => false,
+    }
+}
+
+impl Part {
+    fn matches(&self, segment: &Segment) -> bool {
+        match (self, segment) {
+            (Part::Key(expected), Segment::Map { key } | Segment::Enum { variant: key }) => {
+                key == expected
+            }
+            (Part::Index(range), Segment::Seq { index }) => range.contains(*index),
+            (Part::Any | Part::AnyDepth, _) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Range {
+    const ALL: Range = Range {
+        start: 0,
+        end: None,
+    };
+
+    fn contains(self, index: usize) -> bool {
+        self.start <= index && self.end.map_or(true, |end| index < end)
+    }
+
+    fn parse(range: &str) -> Result<Self, &'static str> {
+        let invalid = "invalid index";
+        if range == "*" {
+            return Ok(Range::ALL);
+        }
+        let (start, end) = match range.split_once("..") {
+            Some(split) => split,
+            None => {
+                let index: usize = range.parse().map_err(|_| invalid)?;
+                return Ok(Range {
+                    start: index,
+                    end: Some(index.checked_add(1).ok_or(invalid)?),
+                });
+            }
+        };
+        let start = if start.is_empty() {
+            0
+        } else {
+            start.parse().map_err(|_| invalid)?
+        };
+        let end = if let Some(last) = end.strip_prefix('=') {
+            let last = last.parse::<usize>().map_err(|_| invalid)?;
+            Some(last.checked_add(1).ok_or(invalid)?)
+        } else if end.is_empty() {
+            None
+        } else {
+            Some(end.parse().map_err(|_| invalid)?)
+        };
+        if end.is_some_and(|end| end < start) {
+            return Err("reversed range");
+        }
+        Ok(Range { start, end })
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.end {
+            None if self.start == 0 => formatter.write_str("*"),
+            None => write!(formatter, "{}..", self.start),
+            Some(end) if end - self.start == 1 => write!(formatter, "{}", self.start),
+            Some(end) => write!(formatter, "{}..{}", self.start, end),
+        }
+    }
+}
+
+impl FromStr for PathPattern {
+    type Err = PatternError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let error = |reason| PatternError::new(pattern, reason);
+        let mut parts = Vec::new();
+        let mut rest = pattern;
+        while !rest.is_empty() || parts.is_empty() {
+            if let Some(inner) = rest.strip_prefix('[') {
+                let end = inner.find(']').ok_or_else(|| error("unclosed `[`"))?;
+                let range = Range::parse(&inner[..end]).map_err(error)?;
+                parts.push(Part::Index(range));
+                rest = &inner[end + 1..];
+            } else {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                parts.push(match &rest[..end] {
+                    "" => return Err(error("empty segment")),
+                    "*" => Part::Any,
+                    "**" => Part::AnyDepth,
+                    key => Part::Key(key.to_owned()),
+                });
+                rest = &rest[end..];
+            }
+            match rest.as_bytes().first() {
+                None | Some(b'[') => {}
+                Some(b'.') => {
+                    rest = &rest[1..];
+                    if rest.is_empty() || rest.starts_with('[') {
+                        return Err(error("empty segment"));
+                    }
+                }
+                Some(_) => return Err(error("expected `.` or `[` after `]`")),
+            }
+        }
+        Ok(PathPattern { parts })
+    }
+}
+
+impl Display for PathPattern {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut separator = "";
+        for part in &self.parts {
+            if let Part::Index(range) = part {
+                write!(formatter, "[{}]", range)?;
+            } else {
+                formatter.write_str(separator)?;
+                match part {
+                    Part::Key(key) => formatter.write_str(key)?,
+                    Part::Any => formatter.write_str("*")?,
+                    Part::AnyDepth => formatter.write_str("**")?,
+                    Part::Index(_) => unreachable!(),
+                }
+            }
+            separator = ".";
+        }
+        Ok(())
+    }
+}
+
+/// Error parsing a [`PathPattern`].
+#[derive(Clone, Debug)]
+pub struct PatternError {
+    pattern: String,
+    reason: &'static str,
+}
+
+impl PatternError {
+    fn new(pattern: &str, reason: &'static str) -> Self {
+        PatternError {
+            pattern: pattern.to_owned(),
+            reason,
+        }
+    }
+}
+
+impl Display for PatternError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "invalid path pattern `{}`: {}",
+            self.pattern, self.reason,
+        )
+    }
+}
+
+impl StdError for PatternError {}
@@ -0,0 +1,165 @@
+use crate::hook::Hook;
+use crate::path::Path;
+use crate::ser::serialize_tracked;
+use crate::{Error, Scalar};
+use alloc::format;
+use alloc::string::{String, ToString as _};
+use serde::ser::{self, Serialize};
+
+/// Largest integer that every IEEE 754 double, and thus JavaScript, can
+/// represent exactly: 2<sup>53</sup> &minus; 1.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Checks that reject values which would not be portable, before the
+/// serializer gets to see them. Map keys are held to the same checks as values,
+/// as well as to [`string_keys`][Policy::string_keys], before they are
+/// serialized.
+///
+/// Formats deal with some values in surprising ways: `serde_json` writes NaN
+/// and infinities as `null`, JavaScript silently rounds integers beyond
+/// 2<sup>53</sup>, and many formats only allow string keys. Serializing
+/// through a policy fails instead, with the path of the offending value. No
+/// checks are enabled by default.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Serialize;
+/// #
+/// use serde_path_to_error::Policy;
+///
+/// #[derive(Serialize)]
+/// struct Metrics {
+///     name: String,
+///     samples: Vec<f64>,
+/// }
+///
+/// let metrics = Metrics {
+///     name: "latency".to_owned(),
+///     samples: vec![1.5, f64::NAN],
+/// };
+///
+/// let policy = Policy::new().finite_floats();
+///
+/// let mut out = Vec::new();
+/// let jser = &mut serde_json::Serializer::new(&mut out);
+/// let err = policy.serialize(&metrics, jser).unwrap_err();
+/// assert_eq!(err.to_string(), "samples[1]: float must be finite, found NaN");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Policy {
+    finite_floats: bool,
+    safe_integers: bool,
+    max_string_len: Option<usize>,
+    string_keys: bool,
+}
+
+impl Policy {
+    /// Policy with no checks enabled.
+    pub fn new() -> Self {
+        Policy {
+            finite_floats: false,
+            safe_integers: false,
+            max_string_len: None,
+            string_keys: false,
+        }
+    }
+
+    /// Policy for output that will be read by JavaScript: floats must be
+    /// finite, integers must be safe and map keys must be strings.
+    pub fn javascript() -> Self {
+        Policy::new().finite_floats().safe_integers().string_keys()
+    }
+
+    /// Rejects NaN and infinite floats.
+    pub fn finite_floats(self) -> Self {
+        Policy {
+            finite_floats: true,
+            ..self
+        }
+    }
+
+    /// Rejects integers beyond &plusmn;(2<sup>53</sup> &minus; 1), which a
+    /// JavaScript number cannot represent exactly.
+    pub fn safe_integers(self) -> Self {
+        Policy {
+            safe_integers: true,
+            ..self
+        }
+    }
+
+    /// Rejects strings longer than the given number of bytes.
+    pub fn max_string_len(self, len: usize) -> Self {
+        Policy {
+            max_string_len: Some(len),
+            ..self
+        }
+    }
+
+    /// Rejects map keys that serialize as anything other than a string, such
+    /// as numbers, booleans or compound values.
+    pub fn string_keys(self) -> Self {
+        Policy {
+            string_keys: true,
+            ..self
+        }
+    }
+
+    /// Serializes the value with the given serializer, failing at the first
+    /// value that the policy rejects.
+    pub fn serialize<T, S>(&self, value: &T, serializer: S) -> Result<S::Ok, Error<S::Error>>
+    where
+        T: ?Sized + Serialize,
+        S: ser::Serializer,
+    {
+        serialize_tracked(serializer, Some(self), |serializer| {
+            value.serialize(serializer)
+        })
+    }
+}
+
+impl Hook for Policy {
+    fn check(&self, _path: &Path, scalar: Scalar) -> Result<(), String> {
+        match scalar {
+            Scalar::F64(v) if self.finite_floats && !v.is_finite() => {
+                Err(format!("float must be finite, found {}", v))
+            }
+            Scalar::I64(v) if self.safe_integers && v.unsigned_abs() > MAX_SAFE_INTEGER => {
+                Err(unsafe_integer(v))
+            }
+            Scalar::I128(v)
+                if self.safe_integers && v.unsigned_abs() > MAX_SAFE_INTEGER as u128 =>
+            {
+                Err(unsafe_integer(v))
+            }
+            Scalar::U64(v) if self.safe_integers && v > MAX_SAFE_INTEGER => Err(unsafe_integer(v)),
+            Scalar::U128(v) if self.safe_integers && v > MAX_SAFE_INTEGER as u128 => {
+                Err(unsafe_integer(v))
+            }
+            Scalar::Str(v) => match self.max_string_len {
+                Some(max) if v.len() > max => Err(format!(
+                    "string must be at most {} bytes long, found {} bytes",
+                    max,
+                    v.len(),
+                )),
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    fn check_key(&self, _path: &Path, string: bool) -> Result<(), String> {
+        if self.string_keys && !string {
+            Err("map key must be a string".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn unsafe_integer(v: impl core::fmt::Display) -> String {
+    format!(
+        "integer must be between -{0} and {0}, found {1}",
+        MAX_SAFE_INTEGER, v,
+    )
+}
@@ -0,0 +1,104 @@
+use crate::hook::Hook;
+use crate::path::Path;
+use crate::pattern::PathPattern;
+use crate::ser::Serializer;
+use crate::Track;
+use serde::ser::Serialize;
+
+/// Serializes a value with everything at the given paths replaced by a mask.
+///
+/// Works with any serializer: wrap the value and serialize the wrapper in its
+/// place. Values are matched by location regardless of their type, so an
+/// entire struct or map matched by a pattern is replaced by a single mask
+/// string, as is `None`. The value at the root is never replaced.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Serialize;
+/// #
+/// use serde_path_to_error::{PathPattern, Redact};
+///
+/// #[derive(Serialize)]
+/// struct Config {
+///     database: Database,
+///     credentials: Credentials,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Database {
+///     url: String,
+///     password: String,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Credentials {
+///     token: String,
+/// }
+///
+/// let config = Config {
+///     database: Database {
+///         url: "postgres://db".to_owned(),
+///         password: "hunter2".to_owned(),
+///     },
+///     credentials: Credentials {
+///         token: "abc123".to_owned(),
+///     },
+/// };
+///
+/// let patterns: Vec<PathPattern> = ["**.password", "credentials.*"]
+///     .iter()
+///     .map(|pattern| pattern.parse().unwrap())
+///     .collect();
+///
+/// let j = serde_json::to_string(&Redact::new(&config, &patterns)).unwrap();
+/// assert_eq!(
+///     j,
+///     r#"{"database":{"url":"postgres://db","password":"[redacted]"},"credentials":{"token":"[redacted]"}}"#,
+/// );
+/// ```
+pub struct Redact<'a, T: ?Sized> {
+    value: &'a T,
+    patterns: &'a [PathPattern],
+    mask: &'a str,
+}
+
+impl<'a, T: ?Sized> Redact<'a, T> {
+    /// Replaces the values matching any of the patterns by `"[redacted]"`.
+    pub fn new(value: &'a T, patterns: &'a [PathPattern]) -> Self {
+        Redact {
+            value,
+            patterns,
+            mask: "[redacted]",
+        }
+    }
+
+    /// Uses a different string in place of the redacted values.
+    pub fn mask(self, mask: &'a str) -> Self {
+        Redact { mask, ..self }
+    }
+}
+
+impl<'a, T> Serialize for Redact<'a, T>
+where
+    T: ?Sized + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut track = Track::new();
+        self.value
+            .serialize(Serializer::new(serializer, &mut track).with_hook(self))
+    }
+}
+
+impl<'a, T: ?Sized> Hook for Redact<'a, T> {
+    fn replace(&self, path: &Path) -> Option<&str> {
+        if self.patterns.iter().any(|pattern| pattern.matches(path)) {
+            Some(self.mask)
+        } else {
+            None
+        }
+    }
+}
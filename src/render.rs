@@ -0,0 +1,190 @@
+use crate::span::Span;
+use crate::Error;
+use core::fmt::{self, Display};
+
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Compiler-style rendering of an [`Error`] against the source text it came
+/// from.
+///
+/// The path is shown as a header, followed by the source lines covered by the
+/// span with the span underlined, and the inner error message. Without a span
+/// only the header and message are shown. Leading and trailing whitespace in
+/// the span is not underlined.
+///
+/// Use `Display`, or [`render`][Report::render] to write into any
+/// `fmt::Write`.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// use serde_path_to_error::Report;
+///
+/// #[derive(Deserialize)]
+/// struct Package {
+///     name: String,
+///     version: String,
+/// }
+///
+/// let j = r#"{
+///     "name": "demo",
+///     "version": 1
+/// }"#;
+///
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+/// let result: Result<Package, _> = serde_path_to_error::deserialize(jd);
+/// let err = match result {
+///     Ok(_) => panic!("expected a type error"),
+///     Err(err) => err,
+/// };
+///
+/// // Span of the `1`, however obtained.
+/// let start = j.find(": 1").unwrap() + 2;
+/// let span = serde_path_to_error::Span::new(start, start + 1);
+///
+/// let report = Report::new(&err, j).span(span).to_string();
+/// assert_eq!(
+///     report,
+///     "\
+/// error: version
+///  --> 3:16
+///   |
+/// 3 |     \"version\": 1
+///   |                ^ invalid type: integer `1`, expected a string at line 3 column 16
+/// ",
+/// );
+/// ```
+pub struct Report<'a, E> {
+    error: &'a Error<E>,
+    source: &'a str,
+    span: Option<Span>,
+    color: bool,
+}
+
+impl<'a, E> Report<'a, E> {
+    /// Report for `error` against `source`, using the error's own
+    /// [span][Error::span] if it has one.
+    pub fn new(error: &'a Error<E>, source: &'a str) -> Self {
+        Report {
+            error,
+            source,
+            span: error.span(),
+            color: false,
+        }
+    }
+
+    /// Underlines the given span instead of the error's own.
+    pub fn span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Whether to highlight the output using ANSI escape codes.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl<'a, E: Display> Report<'a, E> {
+    /// Writes the report into `out`.
+    pub fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        let (red, blue, bold, reset) = if self.color {
+            (BOLD_RED, BOLD_BLUE, BOLD, RESET)
+        } else {
+            ("", "", "", "")
+        };
+
+        write!(out, "{}error{}", red, reset)?;
+        if !self.error.path.is_only_unknown() {
+            write!(out, "{}: {}{}", bold, self.error.path, reset)?;
+        }
+        out.write_char('\n')?;
+
+        let span = match self.span {
+            Some(span) => span.trim(self.source),
+            None => return writeln!(out, "  {}={} {}", blue, reset, self.error.original),
+        };
+
+        let source = self.source;
+        let first = span.line_column(source);
+        let last_line = first.line + source[span.start..span.end].matches('\n').count();
+        let width = digits(last_line);
+
+        writeln!(
+            out,
+            "{:w$}{}-->{} {}:{}",
+            "",
+            blue,
+            reset,
+            first.line,
+            first.column,
+            w = width
+        )?;
+        writeln!(out, "{:w$} {}|{}", "", blue, reset, w = width)?;
+
+        let mut line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let mut line_number = first.line;
+        loop {
+            let line_end = source[line_start..]
+                .find('\n')
+                .map_or(source.len(), |i| line_start + i);
+            let line = source[line_start..line_end].trim_end_matches('\r');
+            writeln!(
+                out,
+                "{}{:<w$} |{} {}",
+                blue,
+                line_number,
+                reset,
+                line,
+                w = width
+            )?;
+
+            // The span may begin or end on the '\r' of a CRLF line ending,
+            // which is not part of `line`.
+            let from = if span.start > line_start {
+                (span.start - line_start).min(line.len())
+            } else {
+                line.len() - line.trim_start().len()
+            };
+            let to = (span.end.min(line_start + line.len()) - line_start).max(from);
+            write!(out, "{:w$} {}|{} ", "", blue, reset, w = width)?;
+            for ch in line[..from].chars() {
+                out.write_char(if ch == '\t' { '\t' } else { ' ' })?;
+            }
+            out.write_str(red)?;
+            let carets = line[from..to].chars().count().max(1);
+            for _ in 0..carets {
+                out.write_char('^')?;
+            }
+            out.write_str(reset)?;
+
+            if line_end >= span.end || line_end == source.len() {
+                return writeln!(out, " {}{}{}", red, self.error.original, reset);
+            }
+            out.write_char('\n')?;
+            line_start = line_end + 1;
+            line_number += 1;
+        }
+    }
+}
+
+impl<'a, E: Display> Display for Report<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f)
+    }
+}
+
+fn digits(mut n: usize) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits
+}
@@ -0,0 +1,397 @@
+use crate::content::{Content, ContentDeserializer, ContentVisitor};
+use crate::de::Deserializer;
+use crate::{scope, Chain, Track, Tracker};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use serde::de::{
+    self, Deserialize, DeserializeSeed, EnumAccess, Unexpected, VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+/// Deserialize a `#[serde(flatten)]` field while keeping track of the path.
+///
+/// Serde buffers the entries of a flattened field before deserializing them,
+/// and the buffered entries are not seen by the [`Deserializer`] wrapper, so
+/// errors inside of a flattened field are ordinarily reported at the path of
+/// the containing struct. Using this as the field's `deserialize_with` replays
+/// the entries through the wrapper instead, so the path continues on into the
+/// flattened value.
+///
+/// The flattened value receives the same entries as it would without this
+/// function: a flattened struct takes only its own fields, leaving the rest to
+/// sibling flattened fields, while a flattened map receives every entry not
+/// taken by the fields before it.
+///
+/// A flattened `Option` is deserialized as usual, without tracking the path
+/// into its contents, since Serde decides whether it is present in a way that
+/// cannot be replayed.
+///
+/// [`Deserializer`]: crate::Deserializer
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// #[derive(Deserialize)]
+/// struct Package {
+///     name: String,
+///     #[serde(flatten, deserialize_with = "serde_path_to_error::flatten")]
+///     metadata: Metadata,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Metadata {
+///     keywords: Vec<String>,
+/// }
+///
+/// let j = r#"{"name": "demo", "keywords": ["serde", 1]}"#;
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+///
+/// let result: Result<Package, _> = serde_path_to_error::deserialize(jd);
+/// let err = match result {
+///     Ok(_) => panic!("expected a type error"),
+///     Err(err) => err,
+/// };
+/// assert_eq!(err.path().to_string(), "keywords[1]");
+/// ```
+pub fn flatten<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(Rebuffer { de: deserializer })
+}
+
+// Deserializer that buffers the input the way the `Deserialize` impl asks for
+// it, then replays the buffer through the wrapper at the current location.
+// Asking the underlying deserializer in the same way matters for Serde's
+// flattened fields, whose deserializer hands out different entries depending
+// on whether a struct or a map is being deserialized.
+struct Rebuffer<D> {
+    de: D,
+}
+
+macro_rules! rebuffer {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: Visitor<'de>,
+            {
+                let content = self.de.$method($($arg,)* ContentVisitor)?;
+                with_location(|chain, track| {
+                    let deserializer = Deserializer {
+                        de: ContentDeserializer::new(content),
+                        chain: chain.clone(),
+                        track,
+                    };
+                    de::Deserializer::$method(deserializer, $($arg,)* visitor)
+                })
+            }
+        )*
+    };
+}
+
+impl<'de, D> de::Deserializer<'de> for Rebuffer<D>
+where
+    D: de::Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    rebuffer! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+    }
+
+    // Serde's flattened fields decide whether an `Option` is present by
+    // attempting to deserialize its contents, which only works if the
+    // `Option`'s own visitor sees the underlying deserializer.
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_option(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_ignored_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+/// Deserialize an ordinary externally tagged enum from the internally tagged
+/// representation, `{"type": "Variant", ...}`, while keeping track of the path.
+///
+/// **The enum must not have the `#[serde(tag = "...")]` attribute.** Remove it
+/// from the enum and name the tag here instead. With the attribute still in
+/// place, every input fails with an error like `invalid type: enum, expected
+/// internally tagged enum Shape`.
+///
+/// Enums with `#[serde(tag = "...")]` are buffered by Serde before their
+/// variant is deserialized, and the buffered content is not seen by the
+/// [`Deserializer`] wrapper, so errors inside of the variant are ordinarily
+/// reported at the path of the enum. Deserializing the enum without the `tag`
+/// attribute through this function instead replays the content through the
+/// wrapper. The variant name does not appear in the path, just as it does not
+/// appear in the input.
+///
+/// Struct, newtype and unit variants are supported, as with Serde's own
+/// internally tagged enums. Since the enum no longer has the `tag` attribute,
+/// its derived `Serialize`, if any, produces the externally tagged
+/// representation instead of the one this function reads.
+///
+/// Enums with `#[serde(untagged)]` are buffered in the same way, but cannot be
+/// handled like this, because a variant that fails cannot be retried once its
+/// visitor has been used. Paths into an untagged enum stop at the enum. Use
+/// [`Untagged`] for those instead.
+///
+/// [`Deserializer`]: crate::Deserializer
+/// [`Untagged`]: crate::Untagged
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// use serde::Deserializer;
+///
+/// #[derive(Deserialize)]
+/// struct Drawing {
+///     #[serde(deserialize_with = "tagged_shape")]
+///     shape: Shape,
+/// }
+///
+/// // No `#[serde(tag = "type")]` here; the tag is named below instead.
+/// #[derive(Deserialize)]
+/// enum Shape {
+///     Circle { radius: f64 },
+///     Square { side: f64 },
+/// }
+///
+/// fn tagged_shape<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Shape, D::Error> {
+///     serde_path_to_error::internally_tagged(deserializer, "type")
+/// }
+///
+/// let j = r#"{"shape": {"type": "Square", "side": "2"}}"#;
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+///
+/// let result: Result<Drawing, _> = serde_path_to_error::deserialize(jd);
+/// let err = match result {
+///     Ok(_) => panic!("expected a type error"),
+///     Err(err) => err,
+/// };
+/// assert_eq!(err.path().to_string(), "shape.side");
+/// ```
+pub fn internally_tagged<'de, D, T>(deserializer: D, tag: &'static str) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let entries = match Content::deserialize(deserializer)? {
+        Content::Map(entries) => entries,
+        other => {
+            return Err(de::Error::invalid_type(
+                other.unexpected(),
+                &"internally tagged enum",
+            ));
+        }
+    };
+
+    let mut variant = None;
+    let mut rest = Vec::with_capacity(entries.len());
+    for (key, value) in entries {
+        if variant.is_none() && key.as_key().as_deref() == Some(tag) {
+            variant = Some(value);
+        } else {
+            rest.push((key, value));
+        }
+    }
+    let variant = match variant {
+        Some(variant) => variant,
+        None => return Err(de::Error::missing_field(tag)),
+    };
+
+    with_location(|chain, track| {
+        T::deserialize(TaggedDeserializer {
+            tag,
+            variant,
+            content: Content::Map(rest),
+            chain,
+            track,
+            marker: PhantomData,
+        })
+    })
+}
+
+// Runs `f` at the location currently being deserialized, or at the root of a
+// throwaway track if there is none.
+fn with_location<R>(f: impl FnOnce(&Chain, Tracker) -> R) -> R {
+    let mut f = Some(f);
+    if let Some(result) = scope::with_current(|chain, track| f.take().unwrap()(chain, track)) {
+        return result;
+    }
+    let track = Track::new();
+    let tracker = Tracker::new(&track);
+    let _scope = scope::enter(&Chain::Root, &tracker);
+    f.take().unwrap()(&Chain::Root, tracker)
+}
+
+struct TaggedDeserializer<'a, 'b, 'de, E> {
+    tag: &'static str,
+    variant: Content<'de>,
+    content: Content<'de>,
+    chain: &'a Chain<'a>,
+    track: Tracker<'b>,
+    marker: PhantomData<E>,
+}
+
+impl<'a, 'b, 'de, E> de::Deserializer<'de> for TaggedDeserializer<'a, 'b, 'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'a, 'b, 'de, E> EnumAccess<'de> for TaggedDeserializer<'a, 'b, 'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+    type Variant = TaggedVariant<'a, 'b, 'de, E>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), E>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let chain = Chain::Struct {
+            parent: self.chain,
+            key: self.tag,
+        };
+        let variant = seed.deserialize(Deserializer {
+            de: ContentDeserializer::new(self.variant),
+            chain,
+            track: self.track,
+        })?;
+        let access = TaggedVariant {
+            content: self.content,
+            chain: self.chain,
+            track: self.track,
+            marker: PhantomData,
+        };
+        Ok((variant, access))
+    }
+}
+
+struct TaggedVariant<'a, 'b, 'de, E> {
+    content: Content<'de>,
+    chain: &'a Chain<'a>,
+    track: Tracker<'b>,
+    marker: PhantomData<E>,
+}
+
+impl<'a, 'b, 'de, E> TaggedVariant<'a, 'b, 'de, E>
+where
+    E: de::Error,
+{
+    fn into_deserializer(self) -> Deserializer<'a, 'b, ContentDeserializer<'de, E>> {
+        Deserializer {
+            de: ContentDeserializer::new(self.content),
+            chain: self.chain.clone(),
+            track: self.track,
+        }
+    }
+}
+
+impl<'a, 'b, 'de, E> VariantAccess<'de> for TaggedVariant<'a, 'b, 'de, E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, E>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.into_deserializer())
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            Unexpected::TupleVariant,
+            &"struct, newtype or unit variant",
+        ))
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(self.into_deserializer(), "", fields, visitor)
+    }
+}
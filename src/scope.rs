@@ -0,0 +1,183 @@
+// Thread-local record of the innermost chain currently being deserialized, so
+// that code running underneath the wrapper (custom Deserialize impls and
+// `deserialize_with` functions, including this crate's own `lenient_option`,
+// `flatten` and `internally_tagged`) can find out where it is and report back
+// to the right `Track`.
+//
+// This is the only unsafe code in the crate. The thread-local holds raw
+// pointers to a `Chain` and a `Tracker` owned by a caller further up the stack.
+// Dereferencing them is sound because a frame is only installed for as long as
+// the `Scope` guard that installed it is alive:
+//
+// - `enter` ties the guard to the lifetime of the chain and tracker it points
+//   to, so the borrow checker rejects a guard that would outlive them.
+// - Guards are only ever held in local variables within this crate, never
+//   leaked or moved elsewhere, so they are dropped in the reverse order of
+//   their creation, whether by returning or by unwinding. Each one restores
+//   the frame that was current before it, so the current frame always belongs
+//   to the innermost live guard. Debug builds check this on every drop.
+// - The frame is thread-local and `Scope` is neither `Send` nor `Sync`, so the
+//   pointers are only ever seen by the thread whose stack they point into.
+//
+// `with_current` hands the referents to a closure that is generic over their
+// lifetime, so nothing borrowed from them can outlive the call.
+//
+// Without the "std" feature there is no thread-local storage to keep this in,
+// and `enter` is a no-op.
+
+#[cfg(feature = "std")]
+use crate::Path;
+use crate::{Chain, Tracker};
+
+#[cfg(feature = "std")]
+mod imp {
+    use crate::{Chain, Tracker};
+    use core::marker::PhantomData;
+    use std::cell::Cell;
+    use std::thread_local;
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Frame {
+        chain: *const Chain<'static>,
+        track: *const Tracker<'static>,
+    }
+
+    thread_local! {
+        static CURRENT: Cell<Option<Frame>> = const { Cell::new(None) };
+    }
+
+    pub struct Scope<'a> {
+        frame: Frame,
+        prev: Option<Frame>,
+        marker: PhantomData<&'a Tracker<'a>>,
+    }
+
+    pub fn enter<'a>(chain: &'a Chain, track: &'a Tracker) -> Scope<'a> {
+        let frame = Frame {
+            chain: (chain as *const Chain).cast::<Chain<'static>>(),
+            track: (track as *const Tracker).cast::<Tracker<'static>>(),
+        };
+        Scope {
+            frame,
+            prev: CURRENT.with(|current| current.replace(Some(frame))),
+            marker: PhantomData,
+        }
+    }
+
+    impl<'a> Drop for Scope<'a> {
+        fn drop(&mut self) {
+            let frame = CURRENT.with(|current| current.replace(self.prev));
+            debug_assert!(frame == Some(self.frame), "scope dropped out of order");
+        }
+    }
+
+    pub fn with_current<R>(f: impl for<'x> FnOnce(&'x Chain<'x>, Tracker<'x>) -> R) -> Option<R> {
+        let frame = CURRENT.with(Cell::get)?;
+        // SAFETY: the frame belongs to the innermost `Scope` that is still
+        // alive on this thread, which borrows the chain and tracker it points
+        // to for as long as it lives; see the comment at the top of this file.
+        // That scope is further up the stack than this call, so the referents
+        // outlive `f`, which cannot hold on to them past its return.
+        let (chain, track) = unsafe { (&*frame.chain, *frame.track) };
+        Some(f(chain, track))
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod imp {
+    use crate::{Chain, Tracker};
+    use core::marker::PhantomData;
+
+    pub struct Scope<'a> {
+        marker: PhantomData<&'a Tracker<'a>>,
+    }
+
+    pub fn enter<'a>(_chain: &'a Chain, _track: &'a Tracker) -> Scope<'a> {
+        Scope {
+            marker: PhantomData,
+        }
+    }
+}
+
+pub(crate) use self::imp::Scope;
+
+#[cfg(feature = "std")]
+pub(crate) use self::imp::with_current;
+
+// Installs `chain` as the current location until the returned guard is dropped.
+// The guard must be held in a local variable; see the comment at the top of
+// this file.
+#[must_use]
+pub(crate) fn enter<'a>(chain: &'a Chain, track: &'a Tracker) -> Scope<'a> {
+    imp::enter(chain, track)
+}
+
+/// Path of the value currently being deserialized.
+///
+/// Returns `Some` when called from code running underneath one of this crate's
+/// entry points, such as a handwritten `Deserialize` impl or a
+/// `deserialize_with` function, and `None` otherwise, including on other
+/// threads.
+///
+/// A [`Deserializer`][crate::Deserializer] wrapper constructed by hand with
+/// `Deserializer::new` is only entered once the `Deserialize` impl of the root
+/// value calls into it, so that impl itself sees `None`. The values nested
+/// inside of it are handed out by the wrapper and see their path as usual.
+///
+/// The path is that of the innermost value the wrapper has handed to a
+/// `Deserialize` impl on this thread. A separate document deserialized from
+/// inside of that impl without going through this crate, such as embedded JSON
+/// passed to `serde_json::from_str`, is invisible to the wrapper, so code
+/// running underneath it sees the path of the enclosing value. Deserialize the
+/// nested document with one of this crate's entry points instead to have
+/// `current_path` report paths within it, relative to the nested document.
+///
+/// This path is also what places the errors kept by [`Lossy`] and
+/// [`Untagged`] within the input. Wherever `current_path` would return `None`,
+/// including everywhere when the `std` feature is disabled, the paths of their
+/// errors start from the collection or enum itself instead of from the root.
+///
+/// [`Lossy`]: crate::Lossy
+/// [`Untagged`]: crate::Untagged
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// use serde::de::{Deserialize, Deserializer, Error};
+///
+/// #[derive(Deserialize)]
+/// struct Manifest {
+///     #[serde(deserialize_with = "relative_path")]
+///     readme: String,
+/// }
+///
+/// fn relative_path<'de, D>(deserializer: D) -> Result<String, D::Error>
+/// where
+///     D: Deserializer<'de>,
+/// {
+///     let path = String::deserialize(deserializer)?;
+///     if path.starts_with('/') {
+///         let location = serde_path_to_error::current_path().unwrap();
+///         let msg = format!("{} must be a relative path, found {:?}", location, path);
+///         return Err(D::Error::custom(msg));
+///     }
+///     Ok(path)
+/// }
+///
+/// let j = r#"{"readme": "/README.md"}"#;
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+///
+/// let result: Result<Manifest, _> = serde_path_to_error::deserialize(jd);
+/// let err = match result {
+///     Ok(_) => panic!("expected an absolute path error"),
+///     Err(err) => err,
+/// };
+/// assert_eq!(err.path().to_string(), "readme");
+/// assert!(err.to_string().starts_with("readme: readme must be a relative path"));
+/// ```
+#[cfg(feature = "std")]
+pub fn current_path() -> Option<Path> {
+    imp::with_current(|chain, track| track.path_of(chain))
+}
@@ -1,5 +1,8 @@
+use crate::hook::Hook;
+use crate::path::Path;
+use crate::walk::Discard;
 use crate::wrap::Wrap;
-use crate::{Chain, Error, Track};
+use crate::{Chain, Error, NodeKind, Scalar, Track, Tracker};
 use alloc::borrow::ToOwned as _;
 use alloc::string::{String, ToString as _};
 use core::cell::Cell;
@@ -8,6 +11,10 @@ use serde::ser::{self, Serialize};
 
 /// Entry point for tracking path to Serialize error.
 ///
+/// The value may be unsized, so trait objects such as `&dyn
+/// erased_serde::Serialize` can be passed directly. See [`serialize_with`] for
+/// serialization that is not expressed as a `Serialize` impl.
+///
 /// # Example
 ///
 /// ```
@@ -52,10 +59,96 @@ where
     T: ?Sized + Serialize,
     S: ser::Serializer,
 {
-    let mut track = Track::new();
-    match T::serialize(value, Serializer::new(serializer, &mut track)) {
+    serialize_tracked(serializer, None, |serializer| value.serialize(serializer))
+}
+
+/// Entry point for tracking path to Serialize error, for serialization that
+/// is driven by a function rather than a `Serialize` impl.
+///
+/// This is the serializing counterpart of
+/// [`deserialize_seed`][crate::deserialize_seed]. The function is handed the
+/// wrapped serializer, so stateful serialization that needs context beyond the
+/// value itself, or type-erased serialization such as
+/// `erased_serde::serialize(value, serializer)`, gets the same path tracking as
+/// [`serialize`].
+///
+/// # Example
+///
+/// ```
+/// use serde::ser::{Error as _, Serialize, SerializeSeq as _, Serializer};
+///
+/// // An interned string, serialized as the string it stands for.
+/// struct Interned<'a> {
+///     table: &'a [&'a str],
+///     id: usize,
+/// }
+///
+/// impl Serialize for Interned<'_> {
+///     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+///     where
+///         S: Serializer,
+///     {
+///         match self.table.get(self.id) {
+///             Some(string) => serializer.serialize_str(string),
+///             None => Err(S::Error::custom(format!("unknown id {}", self.id))),
+///         }
+///     }
+/// }
+///
+/// fn strings<S>(table: &[&str], ids: &[usize], serializer: S) -> Result<S::Ok, S::Error>
+/// where
+///     S: Serializer,
+/// {
+///     let mut seq = serializer.serialize_seq(Some(ids.len()))?;
+///     for &id in ids {
+///         seq.serialize_element(&Interned { table, id })?;
+///     }
+///     seq.end()
+/// }
+///
+/// let table = ["serde", "json"];
+///
+/// let mut out = Vec::new();
+/// let jser = &mut serde_json::Serializer::new(&mut out);
+///
+/// let result = serde_path_to_error::serialize_with(jser, |ser| strings(&table, &[1, 0, 2], ser));
+/// match result {
+///     Ok(_) => panic!("expected an unknown id error"),
+///     Err(err) => assert_eq!(err.to_string(), "[2]: unknown id 2"),
+/// }
+/// ```
+pub fn serialize_with<S, F>(serializer: S, f: F) -> Result<S::Ok, Error<S::Error>>
+where
+    S: ser::Serializer,
+    F: FnOnce(Serializer<S>) -> Result<S::Ok, S::Error>,
+{
+    serialize_tracked(serializer, None, f)
+}
+
+// Hands the wrapped serializer, consulting the hook if there is one, to the
+// function that serializes the value.
+pub(crate) fn serialize_tracked<S, F>(
+    serializer: S,
+    hook: Option<&dyn Hook>,
+    f: F,
+) -> Result<S::Ok, Error<S::Error>>
+where
+    S: ser::Serializer,
+    F: FnOnce(Serializer<S>) -> Result<S::Ok, S::Error>,
+{
+    let track = Track::new();
+    let result = f(Serializer {
+        ser: serializer,
+        chain: &Chain::Root,
+        track: Tracker {
+            hook,
+            ..Tracker::new(&track)
+        },
+    });
+    match result {
         Ok(ok) => Ok(ok),
         Err(err) => Err(Error {
+            span: None,
             path: track.path(),
             original: err,
         }),
@@ -97,7 +190,7 @@ where
 pub struct Serializer<'a, 'b, S> {
     ser: S,
     chain: &'a Chain<'a>,
-    track: &'b Track,
+    track: Tracker<'b>,
 }
 
 impl<'a, 'b, S> Serializer<'a, 'b, S> {
@@ -106,7 +199,15 @@ impl<'a, 'b, S> Serializer<'a, 'b, S> {
         Serializer {
             ser,
             chain: &Chain::Root,
-            track,
+            track: Tracker::new(track),
+        }
+    }
+
+    // Consults the given hook for every value serialized.
+    pub(crate) fn with_hook(self, hook: &'b dyn Hook) -> Self {
+        Serializer {
+            track: self.track.with_hook(hook),
+            ..self
         }
     }
 }
@@ -128,6 +229,8 @@ where
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::Bool(v))?;
         self.ser
             .serialize_bool(v)
             .map_err(|err| track.trigger(chain, err))
@@ -136,6 +239,8 @@ where
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::I64(i64::from(v)))?;
         self.ser
             .serialize_i8(v)
             .map_err(|err| track.trigger(chain, err))
@@ -144,6 +249,8 @@ where
     fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::I64(i64::from(v)))?;
         self.ser
             .serialize_i16(v)
             .map_err(|err| track.trigger(chain, err))
@@ -152,6 +259,8 @@ where
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::I64(i64::from(v)))?;
         self.ser
             .serialize_i32(v)
             .map_err(|err| track.trigger(chain, err))
@@ -160,6 +269,8 @@ where
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::I64(v))?;
         self.ser
             .serialize_i64(v)
             .map_err(|err| track.trigger(chain, err))
@@ -168,6 +279,8 @@ where
     fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::I128(v))?;
         self.ser
             .serialize_i128(v)
             .map_err(|err| track.trigger(chain, err))
@@ -176,6 +289,8 @@ where
     fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::U64(u64::from(v)))?;
         self.ser
             .serialize_u8(v)
             .map_err(|err| track.trigger(chain, err))
@@ -184,6 +299,8 @@ where
     fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::U64(u64::from(v)))?;
         self.ser
             .serialize_u16(v)
             .map_err(|err| track.trigger(chain, err))
@@ -192,6 +309,8 @@ where
     fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::U64(u64::from(v)))?;
         self.ser
             .serialize_u32(v)
             .map_err(|err| track.trigger(chain, err))
@@ -200,6 +319,8 @@ where
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::U64(v))?;
         self.ser
             .serialize_u64(v)
             .map_err(|err| track.trigger(chain, err))
@@ -208,6 +329,8 @@ where
     fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::U128(v))?;
         self.ser
             .serialize_u128(v)
             .map_err(|err| track.trigger(chain, err))
@@ -216,6 +339,8 @@ where
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::F64(f64::from(v)))?;
         self.ser
             .serialize_f32(v)
             .map_err(|err| track.trigger(chain, err))
@@ -224,6 +349,8 @@ where
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::F64(v))?;
         self.ser
             .serialize_f64(v)
             .map_err(|err| track.trigger(chain, err))
@@ -232,6 +359,8 @@ where
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::Char(v))?;
         self.ser
             .serialize_char(v)
             .map_err(|err| track.trigger(chain, err))
@@ -240,6 +369,8 @@ where
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::Str(v))?;
         self.ser
             .serialize_str(v)
             .map_err(|err| track.trigger(chain, err))
@@ -248,6 +379,8 @@ where
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::Bytes(v))?;
         self.ser
             .serialize_bytes(v)
             .map_err(|err| track.trigger(chain, err))
@@ -256,6 +389,8 @@ where
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::None)?;
         self.ser
             .serialize_none()
             .map_err(|err| track.trigger(chain, err))
@@ -267,14 +402,24 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        // The contents of an option or newtype add nothing to the path, but
+        // still go through the wrapper so that the path continues on into
+        // them rather than stopping here.
         self.ser
-            .serialize_some(value)
+            .serialize_some(&TrackedValue::new(
+                value,
+                &Chain::Some { parent: chain },
+                track,
+            ))
             .map_err(|err| track.trigger(chain, err))
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::Unit)?;
         self.ser
             .serialize_unit()
             .map_err(|err| track.trigger(chain, err))
@@ -283,6 +428,8 @@ where
     fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::Unit)?;
         self.ser
             .serialize_unit_struct(name)
             .map_err(|err| track.trigger(chain, err))
@@ -296,6 +443,8 @@ where
     ) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_leaf(chain, Scalar::Str(variant))?;
         self.ser
             .serialize_unit_variant(name, variant_index, variant)
             .map_err(|err| track.trigger(chain, err))
@@ -311,8 +460,10 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        let inner = Chain::NewtypeStruct { parent: chain };
         self.ser
-            .serialize_newtype_struct(name, value)
+            .serialize_newtype_struct(name, &TrackedValue::new(value, &inner, track))
             .map_err(|err| track.trigger(chain, err))
     }
 
@@ -328,16 +479,28 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_variant(chain, variant);
+        let inner = Chain::NewtypeVariant { parent: chain };
         self.ser
-            .serialize_newtype_variant(name, variant_index, variant, value)
+            .serialize_newtype_variant(
+                name,
+                variant_index,
+                variant,
+                &TrackedValue::new(value, &inner, track),
+            )
             .map_err(|err| track.trigger(chain, err))
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
         match self.ser.serialize_seq(len) {
-            Ok(delegate) => Ok(WrapSeq::new(delegate, chain, track)),
+            Ok(delegate) => {
+                track.serialize_enter(chain, NodeKind::Seq);
+                Ok(WrapSeq::new(delegate, chain, track))
+            }
             Err(err) => Err(track.trigger(chain, err)),
         }
     }
@@ -345,8 +508,12 @@ where
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
         match self.ser.serialize_tuple(len) {
-            Ok(delegate) => Ok(WrapSeq::new(delegate, chain, track)),
+            Ok(delegate) => {
+                track.serialize_enter(chain, NodeKind::Seq);
+                Ok(WrapSeq::new(delegate, chain, track))
+            }
             Err(err) => Err(track.trigger(chain, err)),
         }
     }
@@ -358,8 +525,12 @@ where
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
         match self.ser.serialize_tuple_struct(name, len) {
-            Ok(delegate) => Ok(WrapSeq::new(delegate, chain, track)),
+            Ok(delegate) => {
+                track.serialize_enter(chain, NodeKind::Seq);
+                Ok(WrapSeq::new(delegate, chain, track))
+            }
             Err(err) => Err(track.trigger(chain, err)),
         }
     }
@@ -373,11 +544,16 @@ where
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_variant(chain, variant);
         match self
             .ser
             .serialize_tuple_variant(name, variant_index, variant, len)
         {
-            Ok(delegate) => Ok(WrapSeq::new(delegate, chain, track)),
+            Ok(delegate) => {
+                track.serialize_enter(chain, NodeKind::Seq);
+                Ok(WrapSeq::new(delegate, chain, track))
+            }
             Err(err) => Err(track.trigger(chain, err)),
         }
     }
@@ -385,8 +561,12 @@ where
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
         match self.ser.serialize_map(len) {
-            Ok(delegate) => Ok(WrapMap::new(delegate, chain, track)),
+            Ok(delegate) => {
+                track.serialize_enter(chain, NodeKind::Map);
+                Ok(WrapMap::new(delegate, chain, track))
+            }
             Err(err) => Err(track.trigger(chain, err)),
         }
     }
@@ -398,8 +578,12 @@ where
     ) -> Result<Self::SerializeStruct, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
         match self.ser.serialize_struct(name, len) {
-            Ok(delegate) => Ok(Wrap::new(delegate, chain, track)),
+            Ok(delegate) => {
+                track.serialize_enter(chain, NodeKind::Map);
+                Ok(Wrap::new(delegate, chain, track))
+            }
             Err(err) => Err(track.trigger(chain, err)),
         }
     }
@@ -413,11 +597,16 @@ where
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        track.serialize_variant(chain, variant);
         match self
             .ser
             .serialize_struct_variant(name, variant_index, variant, len)
         {
-            Ok(delegate) => Ok(Wrap::new(delegate, chain, track)),
+            Ok(delegate) => {
+                track.serialize_enter(chain, NodeKind::Map);
+                Ok(Wrap::new(delegate, chain, track))
+            }
             Err(err) => Err(track.trigger(chain, err)),
         }
     }
@@ -428,8 +617,19 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_node(chain)?;
+        if track.hook.is_none() {
+            return self
+                .ser
+                .collect_str(value)
+                .map_err(|err| track.trigger(chain, err));
+        }
+        // Formatted once, so that the hook sees the same string that the
+        // serializer receives.
+        let string = value.to_string();
+        track.serialize_leaf(chain, Scalar::Str(&string))?;
         self.ser
-            .collect_str(value)
+            .collect_str(&string)
             .map_err(|err| track.trigger(chain, err))
     }
 
@@ -441,11 +641,11 @@ where
 struct TrackedValue<'a, 'b, X> {
     value: X,
     chain: &'a Chain<'a>,
-    track: &'b Track,
+    track: Tracker<'b>,
 }
 
 impl<'a, 'b, X> TrackedValue<'a, 'b, X> {
-    fn new(value: X, chain: &'a Chain<'a>, track: &'b Track) -> Self {
+    fn new(value: X, chain: &'a Chain<'a>, track: Tracker<'b>) -> Self {
         TrackedValue {
             value,
             chain,
@@ -464,6 +664,13 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        if let Some(hook) = track.hook {
+            if let Some(replacement) = hook.replace(&track.path_of(chain)) {
+                return serializer
+                    .serialize_str(replacement)
+                    .map_err(|err| track.trigger(chain, err));
+            }
+        }
         self.value
             .serialize(Serializer {
                 ser: serializer,
@@ -478,11 +685,11 @@ pub struct WrapSeq<'a, 'b, S> {
     delegate: S,
     chain: &'a Chain<'a>,
     index: usize,
-    track: &'b Track,
+    track: Tracker<'b>,
 }
 
 impl<'a, 'b, S> WrapSeq<'a, 'b, S> {
-    fn new(delegate: S, chain: &'a Chain<'a>, track: &'b Track) -> Self {
+    fn new(delegate: S, chain: &'a Chain<'a>, track: Tracker<'b>) -> Self {
         WrapSeq {
             delegate,
             chain,
@@ -518,6 +725,7 @@ where
     fn end(self) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_exit(chain)?;
         self.delegate.end().map_err(|err| track.trigger(chain, err))
     }
 }
@@ -548,6 +756,7 @@ where
     fn end(self) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_exit(chain)?;
         self.delegate.end().map_err(|err| track.trigger(chain, err))
     }
 }
@@ -578,6 +787,7 @@ where
     fn end(self) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_exit(chain)?;
         self.delegate.end().map_err(|err| track.trigger(chain, err))
     }
 }
@@ -608,6 +818,7 @@ where
     fn end(self) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_exit(chain)?;
         self.delegate.end().map_err(|err| track.trigger(chain, err))
     }
 }
@@ -616,11 +827,11 @@ pub struct WrapMap<'a, 'b, S> {
     delegate: S,
     chain: &'a Chain<'a>,
     key: Cell<Option<String>>,
-    track: &'b Track,
+    track: Tracker<'b>,
 }
 
 impl<'a, 'b, S> WrapMap<'a, 'b, S> {
-    fn new(delegate: S, chain: &'a Chain<'a>, track: &'b Track) -> Self {
+    fn new(delegate: S, chain: &'a Chain<'a>, track: Tracker<'b>) -> Self {
         WrapMap {
             delegate,
             chain,
@@ -630,6 +841,57 @@ impl<'a, 'b, S> WrapMap<'a, 'b, S> {
     }
 }
 
+impl<'a, 'b, S> WrapMap<'a, 'b, S>
+where
+    S: ser::SerializeMap,
+{
+    // Checks a key against the hook before the delegate sees it, since the
+    // delegate may already have written the key out by the time it returns.
+    // The key is serialized to nowhere, first to find out its path and then to
+    // check each of its scalars at that path.
+    fn check_key<T>(&self, key: &T, hook: &dyn Hook) -> Result<(), S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        let string_key = Cell::new(false);
+        if CaptureKey::new(&self.key, &string_key, key)
+            .serialize(Discard)
+            .is_err()
+        {
+            // Left for the delegate to fail on.
+            return Ok(());
+        }
+        let entry = match self.key.take() {
+            Some(key) => Chain::Map { parent: chain, key },
+            None => Chain::NonStringKey { parent: chain },
+        };
+        track.serialize_key(&entry, string_key.get())?;
+        let scalars = KeyScalars(hook);
+        let key_track = Tracker {
+            hook: Some(&scalars),
+            ..track
+        };
+        key.serialize(Serializer {
+            ser: Discard,
+            chain: &entry,
+            track: key_track,
+        })
+        .map_err(ser::Error::custom)
+    }
+}
+
+// Hook applying only the scalar checks of another hook, for the scalars of a
+// map key, which are not values of their own.
+struct KeyScalars<'a>(&'a dyn Hook);
+
+impl<'a> Hook for KeyScalars<'a> {
+    fn check(&self, path: &Path, scalar: Scalar) -> Result<(), String> {
+        self.0.check(path, scalar)
+    }
+}
+
 impl<'a, 'b, S> ser::SerializeMap for WrapMap<'a, 'b, S>
 where
     S: ser::SerializeMap,
@@ -643,9 +905,12 @@ where
     {
         let chain = self.chain;
         let track = self.track;
+        if let Some(hook) = track.hook {
+            self.check_key(key, hook)?;
+        }
         self.key.set(None);
         self.delegate
-            .serialize_key(&CaptureKey::new(&self.key, key))
+            .serialize_key(&CaptureKey::new(&self.key, &Cell::new(false), key))
             .map_err(|err| track.trigger(chain, err))
     }
 
@@ -667,6 +932,7 @@ where
     fn end(self) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_exit(chain)?;
         self.delegate.end().map_err(|err| track.trigger(chain, err))
     }
 }
@@ -693,6 +959,7 @@ where
     fn end(self) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_exit(chain)?;
         self.delegate.end().map_err(|err| track.trigger(chain, err))
     }
 
@@ -727,6 +994,7 @@ where
     fn end(self) -> Result<Self::Ok, Self::Error> {
         let chain = self.chain;
         let track = self.track;
+        track.serialize_exit(chain)?;
         self.delegate.end().map_err(|err| track.trigger(chain, err))
     }
 
@@ -741,12 +1009,18 @@ where
 
 struct CaptureKey<'a, T> {
     out: &'a Cell<Option<String>>,
+    // Whether the key serializes as a string, as opposed to a number or bool.
+    string: &'a Cell<bool>,
     delegate: T,
 }
 
 impl<'a, T> CaptureKey<'a, T> {
-    fn new(out: &'a Cell<Option<String>>, delegate: T) -> Self {
-        CaptureKey { out, delegate }
+    fn new(out: &'a Cell<Option<String>>, string: &'a Cell<bool>, delegate: T) -> Self {
+        CaptureKey {
+            out,
+            string,
+            delegate,
+        }
     }
 }
 
@@ -759,7 +1033,7 @@ where
         S: ser::Serializer,
     {
         self.delegate
-            .serialize(CaptureKey::new(self.out, serializer))
+            .serialize(CaptureKey::new(self.out, self.string, serializer))
     }
 }
 
@@ -842,11 +1116,13 @@ where
     }
 
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.string.set(true);
         self.delegate.serialize_char(v)
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
         self.out.set(Some(v.to_owned()));
+        self.string.set(true);
         self.delegate.serialize_str(v)
     }
 
@@ -863,7 +1139,7 @@ where
         T: ?Sized + Serialize,
     {
         self.delegate
-            .serialize_some(&CaptureKey::new(self.out, value))
+            .serialize_some(&CaptureKey::new(self.out, self.string, value))
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
@@ -881,6 +1157,7 @@ where
         variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
         self.out.set(Some(variant.to_owned()));
+        self.string.set(true);
         self.delegate
             .serialize_unit_variant(name, variant_index, variant)
     }
@@ -894,7 +1171,7 @@ where
         T: ?Sized + Serialize,
     {
         self.delegate
-            .serialize_newtype_struct(name, &CaptureKey::new(self.out, value))
+            .serialize_newtype_struct(name, &CaptureKey::new(self.out, self.string, value))
     }
 
     fn serialize_newtype_variant<T>(
@@ -983,6 +1260,7 @@ where
         T: ?Sized + Display,
     {
         self.out.set(Some(value.to_string()));
+        self.string.set(true);
         self.delegate.collect_str(value)
     }
 
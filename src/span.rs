@@ -0,0 +1,125 @@
+use core::cell::Cell;
+use core::ops::Range;
+
+/// Byte range of a value within the input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// Byte offset of the first byte of the value.
+    pub start: usize,
+    /// Byte offset one past the last byte of the value.
+    pub end: usize,
+}
+
+/// Line and column of a position within the input, both starting at 1.
+///
+/// The column counts characters, not bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LineColumn {
+    /// Line number, starting at 1 for the first line.
+    pub line: usize,
+    /// Column number within the line, counting characters rather than bytes,
+    /// starting at 1 for the first character.
+    pub column: usize,
+}
+
+impl Span {
+    /// Span from byte offset `start` up to but not including byte offset
+    /// `end`.
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// Line and column at which this span starts within `source`.
+    ///
+    /// Offsets past the end of `source`, or in the middle of a multi-byte
+    /// character, are clamped to the nearest preceding character boundary.
+    pub fn line_column(&self, source: &str) -> LineColumn {
+        let offset = floor_char_boundary(source, self.start);
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        LineColumn {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    // Clamps the span to `source` and removes surrounding whitespace. A span of
+    // nothing but whitespace becomes empty where it started.
+    pub(crate) fn trim(self, source: &str) -> Span {
+        let start = floor_char_boundary(source, self.start);
+        let end = floor_char_boundary(source, self.end.max(start));
+        let text = &source[start..end];
+        let trimmed = text.trim_start();
+        if trimmed.is_empty() {
+            return Span::new(start, start);
+        }
+        let start = start + (text.len() - trimmed.len());
+        Span::new(start, start + trimmed.trim_end().len())
+    }
+}
+
+// Largest character boundary of `source` not greater than `offset`.
+pub(crate) fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
+
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        Span::new(range.start, range.end)
+    }
+}
+
+/// Source of the current offset into the input being deserialized.
+///
+/// Pass one to [`deserialize_with_position`][crate::deserialize_with_position]
+/// or [`Deserializer::with_position`][crate::Deserializer::with_position] to
+/// have errors carry the [`Span`] of the value that failed. The offset is read as each value is
+/// started and again when it fails, so it should advance as the deserializer
+/// consumes its input.
+///
+/// Spans are only as accurate as the offsets. Serde gives no access to the
+/// deserializer while it is running, so the offset has to come from outside,
+/// typically from a reader that counts the bytes it hands out. That only works
+/// if the deserializer reads no further ahead than it has parsed:
+///
+/// - `serde_json::Deserializer::from_reader` reads one byte at a time and looks
+///   ahead by at most one byte, so a counting reader passed to it directly
+///   yields spans that are exact up to that byte. Wrapping the counting reader
+///   in a `BufReader` does not work, as the count then jumps ahead a whole
+///   buffer at a time.
+/// - `serde_json::StreamDeserializer::byte_offset` is exact, but only between
+///   values of the stream, not while one is being deserialized, so it cannot
+///   serve as a `Position`.
+/// - For input that is entirely in memory, such as with
+///   `serde_json::from_str`, no count is available. With the `json-span`
+///   feature, `locate_json` finds the span of an error's path in the source
+///   text after the fact instead.
+pub trait Position {
+    /// Number of bytes of input consumed so far.
+    fn offset(&self) -> usize;
+}
+
+impl Position for Cell<usize> {
+    fn offset(&self) -> usize {
+        self.get()
+    }
+}
+
+impl<P> Position for &P
+where
+    P: ?Sized + Position,
+{
+    fn offset(&self) -> usize {
+        (**self).offset()
+    }
+}
@@ -0,0 +1,231 @@
+use crate::lossy::{Buffer, Buffered};
+#[cfg(feature = "std")]
+use crate::scope;
+use crate::{Chain, Error, Track, Tracker};
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use serde::de::{self, Deserialize, DeserializeSeed as _};
+
+/// Deserialize an untagged enum by trying each variant in turn, keeping the
+/// error of every variant that did not match.
+///
+/// `#[serde(untagged)]` reports only "data did not match any variant" when no
+/// variant matches. This is a replacement to use from a handwritten
+/// `Deserialize` impl or a `deserialize_with` function: the input is buffered,
+/// then deserialized as the type of each [`variant`][Untagged::variant] in
+/// order until one succeeds. If none does, the error lists the path and message
+/// of each variant's failure, relative to the enum.
+///
+/// When used underneath one of this crate's entry points, each variant is
+/// deserialized at the location of the enum, so the errors kept for the
+/// variants carry the full path from the root of the input, and the observer,
+/// if any, sees the values of every variant as it is tried. Since the variants
+/// are deserialized from the buffered input, their errors all carry the span
+/// of the enum as a whole rather than of the value that failed. Elsewhere,
+/// paths are relative to the enum, as explained under [`current_path`].
+///
+/// [`current_path`]: crate::current_path
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// #
+/// use serde::{Deserialize, Deserializer};
+/// use serde_path_to_error::Untagged;
+///
+/// #[derive(Debug)]
+/// enum Dependency {
+///     Simple(String),
+///     Detailed(DetailedDependency),
+/// }
+///
+/// #[derive(Deserialize, Debug)]
+/// struct DetailedDependency {
+///     version: String,
+///     features: Vec<String>,
+/// }
+///
+/// impl<'de> Deserialize<'de> for Dependency {
+///     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+///         Untagged::new(deserializer, "Dependency")?
+///             .variant("Simple", Dependency::Simple)
+///             .variant("Detailed", Dependency::Detailed)
+///             .finish()
+///     }
+/// }
+///
+/// let j = r#"{"version": "1.0", "features": ["derive", 1]}"#;
+/// let jd = &mut serde_json::Deserializer::from_str(j);
+///
+/// let result: Result<Dependency, _> = serde_path_to_error::deserialize(jd);
+/// let err = match result {
+///     Ok(_) => panic!("expected a type error"),
+///     Err(err) => err,
+/// };
+/// assert_eq!(
+///     err.to_string(),
+///     "data did not match any variant of untagged enum Dependency\n\
+///      - Simple: invalid type: map, expected a string\n\
+///      - Detailed: features[1]: invalid type: integer `1`, expected a string",
+/// );
+/// ```
+pub struct Untagged<'de, T, E> {
+    name: &'static str,
+    buffered: Buffered<'de>,
+    depth: usize,
+    value: Option<T>,
+    errors: Vec<(&'static str, Error<E>)>,
+}
+
+impl<'de, T, E> Untagged<'de, T, E>
+where
+    E: de::Error,
+{
+    /// Buffers the input of an untagged enum with the given name.
+    pub fn new<D>(deserializer: D, name: &'static str) -> Result<Self, E>
+    where
+        D: de::Deserializer<'de, Error = E>,
+    {
+        Ok(Untagged {
+            name,
+            buffered: Buffer::start().deserialize(deserializer)?,
+            depth: depth(),
+            value: None,
+            errors: Vec::new(),
+        })
+    }
+
+    /// Tries deserializing the input as `V`, converting it into the enum using
+    /// `f`. Does nothing if an earlier variant already matched.
+    pub fn variant<V>(mut self, name: &'static str, f: impl FnOnce(V) -> T) -> Self
+    where
+        V: Deserialize<'de>,
+    {
+        if self.value.is_none() {
+            match self.replay() {
+                Ok(value) => self.value = Some(f(value)),
+                Err(err) => self.errors.push((name, err)),
+            }
+        }
+        self
+    }
+
+    // Replay the buffered input into V at the location of the enum, under the
+    // same base path and observer as the enclosing document. Falls back to an
+    // empty path when there is no such location.
+    fn replay<V>(&self) -> Result<V, Error<E>>
+    where
+        V: Deserialize<'de>,
+    {
+        #[cfg(feature = "std")]
+        {
+            let current = scope::with_current(|chain, outer| {
+                // Offsets do not advance while replaying, so the position is
+                // left out in favor of the span of the buffered input. Faults
+                // from the hook would be taken for a variant not matching.
+                let track = outer.child();
+                let tracker = Tracker {
+                    track: &track,
+                    position: None,
+                    hook: None,
+                    ..outer
+                };
+                let result = self.buffered.clone().replay_at(chain, tracker);
+                if result.is_ok() {
+                    outer.adopt(track);
+                }
+                result
+            });
+            if let Some(result) = current {
+                return result;
+            }
+        }
+        let track = Track::recovering();
+        self.buffered
+            .clone()
+            .replay_at(&Chain::Root, Tracker::new(&track))
+    }
+
+    /// The value of the first variant that matched, or else an error listing
+    /// why each variant did not match.
+    pub fn finish(self) -> Result<T, E>
+    where
+        E: Display,
+    {
+        self.into_result().map_err(E::custom)
+    }
+
+    /// Like [`finish`][Untagged::finish], but returns the error of each variant
+    /// separately rather than formatted into a single message.
+    pub fn into_result(self) -> Result<T, UntaggedError<E>> {
+        match self.value {
+            Some(value) => Ok(value),
+            None => Err(UntaggedError {
+                name: self.name,
+                depth: self.depth,
+                errors: self.errors,
+            }),
+        }
+    }
+}
+
+/// Error from an [`Untagged`] enum for which no variant matched.
+#[derive(Clone, Debug)]
+pub struct UntaggedError<E> {
+    name: &'static str,
+    // Number of path segments leading up to the enum, which are left out when
+    // displaying the path of each variant's error.
+    depth: usize,
+    errors: Vec<(&'static str, Error<E>)>,
+}
+
+impl<E> UntaggedError<E> {
+    /// The error of each variant that was tried, by variant name, in the order
+    /// the variants were tried.
+    pub fn variants(&self) -> &[(&'static str, Error<E>)] {
+        &self.errors
+    }
+
+    /// The error of the variant whose error path goes deepest into the input,
+    /// which is most likely the one that was intended. Ties go to the variant
+    /// tried first.
+    pub fn closest(&self) -> Option<&(&'static str, Error<E>)> {
+        self.errors
+            .iter()
+            .rev()
+            .max_by_key(|(_name, err)| err.path().iter().len())
+    }
+}
+
+impl<E: Display> Display for UntaggedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "data did not match any variant of untagged enum {}",
+            self.name,
+        )?;
+        for (name, err) in &self.errors {
+            write!(f, "\n- {}: ", name)?;
+            let path = err.path().skip(self.depth);
+            if !path.is_only_unknown() {
+                write!(f, "{}: ", path)?;
+            }
+            write!(f, "{}", err.inner())?;
+        }
+        Ok(())
+    }
+}
+
+// Number of path segments leading up to the value currently being
+// deserialized.
+fn depth() -> usize {
+    #[cfg(feature = "std")]
+    {
+        scope::with_current(|chain, track| track.path_of(chain).iter().len()).unwrap_or(0)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        0
+    }
+}
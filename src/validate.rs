@@ -0,0 +1,402 @@
+use crate::hook::Hook;
+use crate::path::{Path, Segment};
+use crate::pattern::{PathPattern, PatternError};
+use crate::ser::serialize_tracked;
+use crate::walk::{walk, ScalarValue};
+use crate::{Error, NodeKind, Scalar};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::cmp::Ordering;
+use core::fmt::{self, Display};
+use serde::ser::{self, Serialize, StdError};
+
+/// Set of rules checked against the values at matching paths.
+///
+/// Validation walks a value as it would be serialized, so it works for any
+/// `Serialize` type, whether about to be serialized or just deserialized. Every
+/// violation is reported as an [`Error`] with the path of the offending value,
+/// and displays just like a deserialization error: `dependencies.serde: must
+/// not be empty`.
+///
+/// Each rule applies to the values whose path matches its [`PathPattern`].
+/// Wildcards select values by the kind of segment leading to them, for
+/// example `**[*]` for every element of every sequence.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Serialize;
+/// #
+/// use serde_path_to_error::{Rule, Rules};
+/// use std::collections::BTreeMap as Map;
+///
+/// #[derive(Serialize)]
+/// struct Config {
+///     port: u16,
+///     hosts: Vec<String>,
+///     dependencies: Map<String, Dependency>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Dependency {
+///     version: Option<String>,
+///     path: Option<String>,
+/// }
+///
+/// let rules = Rules::new()
+///     .rule("port", Rule::range(1024.0, 65535.0))?
+///     .rule("hosts", Rule::non_empty())?
+///     .rule("dependencies.*", Rule::exclusive(&["version", "path"]))?;
+///
+/// let config = Config {
+///     port: 80,
+///     hosts: Vec::new(),
+///     dependencies: Map::from([(
+///         "serde".to_owned(),
+///         Dependency {
+///             version: Some("1.0".to_owned()),
+///             path: Some("../serde".to_owned()),
+///         },
+///     )]),
+/// };
+///
+/// let violations: Vec<String> = rules
+///     .validate(&config)
+///     .unwrap_err()
+///     .iter()
+///     .map(ToString::to_string)
+///     .collect();
+///
+/// assert_eq!(
+///     violations,
+///     [
+///         "port: must be between 1024 and 65535, found 80",
+///         "hosts: must not be empty",
+///         "dependencies.serde: `version` and `path` are mutually exclusive",
+///     ],
+/// );
+/// # Ok::<(), serde_path_to_error::PatternError>(())
+/// ```
+#[derive(Default)]
+pub struct Rules {
+    rules: Vec<(PathPattern, Rule)>,
+}
+
+/// Check performed by [`Rules`] on the values at matching paths.
+pub struct Rule {
+    kind: RuleKind,
+}
+
+enum RuleKind {
+    Range {
+        min: f64,
+        max: f64,
+    },
+    NonEmpty,
+    Exclusive(Vec<String>),
+    Str(String, Box<dyn Fn(&str) -> bool + Send + Sync>),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Rules {
+    /// Empty set of rules, which every value satisfies.
+    pub fn new() -> Self {
+        Rules { rules: Vec::new() }
+    }
+
+    /// Adds a rule applying to the values whose path matches the given
+    /// pattern. Fails if the pattern does not parse.
+    pub fn rule(mut self, pattern: &str, rule: Rule) -> Result<Self, PatternError> {
+        self.rules.push((pattern.parse()?, rule));
+        Ok(self)
+    }
+
+    /// Checks every rule against the given value, returning all violations.
+    ///
+    /// An error from the value's `Serialize` impl is reported as a violation
+    /// too, after which nothing more is checked.
+    pub fn validate<T>(&self, value: &T) -> Result<(), Vec<Error<Violation>>>
+    where
+        T: ?Sized + Serialize,
+    {
+        let check = Check::new(self, false);
+        let result = walk(value, &check);
+        let mut violations = check.violations.into_inner();
+        if let Err(err) = result {
+            let message = err.inner().to_string();
+            violations.push(Error::new(err.path().clone(), Violation { message }));
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Serializes the value with the given serializer, failing at the first
+    /// value that breaks a rule.
+    ///
+    /// A rule on a scalar fails before the scalar is serialized. A rule on a
+    /// sequence, map or struct, such as [`Rule::non_empty`], can only be
+    /// checked once its contents have been serialized, and fails before it is
+    /// ended.
+    pub fn serialize<T, S>(&self, value: &T, serializer: S) -> Result<S::Ok, Error<S::Error>>
+    where
+        T: ?Sized + Serialize,
+        S: ser::Serializer,
+    {
+        let check = Check::new(self, true);
+        serialize_tracked(serializer, Some(&check), |serializer| {
+            value.serialize(serializer)
+        })
+    }
+}
+
+impl Rule {
+    /// Numbers must be within `min..=max`. Integers are compared with the
+    /// bounds exactly, without first being converted to floating point.
+    pub fn range(min: f64, max: f64) -> Self {
+        Rule {
+            kind: RuleKind::Range { min, max },
+        }
+    }
+
+    /// Strings, sequences and maps must not be empty.
+    pub fn non_empty() -> Self {
+        Rule {
+            kind: RuleKind::NonEmpty,
+        }
+    }
+
+    /// At most one of the given keys may be present in a map or struct.
+    /// Entries whose value is `None` do not count as present.
+    pub fn exclusive(keys: &[&str]) -> Self {
+        Rule {
+            kind: RuleKind::Exclusive(keys.iter().map(ToString::to_string).collect()),
+        }
+    }
+
+    /// Strings must satisfy the given predicate, or else are reported with the
+    /// given message.
+    pub fn string<F>(message: &str, predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        Rule {
+            kind: RuleKind::Str(message.to_string(), Box::new(predicate)),
+        }
+    }
+
+    /// Strings must match the given regular expression.
+    #[cfg(feature = "regex")]
+    pub fn regex(regex: regex::Regex) -> Self {
+        Rule {
+            kind: RuleKind::Regex(regex),
+        }
+    }
+}
+
+/// Rule broken by a value, as found by [`Rules::validate`].
+#[derive(Clone, Debug)]
+pub struct Violation {
+    message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl StdError for Violation {}
+
+struct Check<'a> {
+    rules: &'a Rules,
+    // Whether to fail at the first violation, as when serializing, rather
+    // than collect them all.
+    fail_fast: bool,
+    frames: RefCell<Vec<Frame>>,
+    violations: RefCell<Vec<Error<Violation>>>,
+}
+
+// Contents seen so far of a sequence, map or struct being walked.
+struct Frame {
+    len: usize,
+    keys: Vec<String>,
+}
+
+impl<'a> Check<'a> {
+    fn new(rules: &'a Rules, fail_fast: bool) -> Self {
+        Check {
+            rules,
+            fail_fast,
+            frames: RefCell::new(Vec::new()),
+            violations: RefCell::new(Vec::new()),
+        }
+    }
+
+    // The first violation found at the value just seen, when failing fast.
+    fn enforce(&self) -> Result<(), String> {
+        if !self.fail_fast {
+            return Ok(());
+        }
+        match self.violations.borrow_mut().drain(..).next() {
+            Some(violation) => Err(violation.into_inner().message),
+            None => Ok(()),
+        }
+    }
+
+    fn matching<'p>(&'p self, path: &'p Path) -> impl Iterator<Item = &'a RuleKind> + 'p {
+        let rules = self.rules;
+        rules
+            .rules
+            .iter()
+            .filter(move |(pattern, _rule)| pattern.matches(path))
+            .map(|(_pattern, rule)| &rule.kind)
+    }
+
+    fn violation(&self, path: &Path, message: String) {
+        let violation = Error::new(path.clone(), Violation { message });
+        self.violations.borrow_mut().push(violation);
+    }
+
+    // Records a value within the innermost sequence, map or struct.
+    fn element(&self, path: &Path, present: bool) {
+        if let Some(frame) = self.frames.borrow_mut().last_mut() {
+            frame.len += 1;
+            if let (true, Some(Segment::Map { key })) = (present, path.iter().next_back()) {
+                frame.keys.push(key.clone());
+            }
+        }
+    }
+}
+
+impl<'a> Hook for Check<'a> {
+    fn enter(&self, path: &Path, _kind: NodeKind) {
+        self.element(path, true);
+        self.frames.borrow_mut().push(Frame {
+            len: 0,
+            keys: Vec::new(),
+        });
+    }
+
+    fn leaf(&self, path: &Path, scalar: Scalar) {
+        self.element(path, !matches!(scalar, Scalar::None));
+        for rule in self.matching(path) {
+            match (rule, scalar) {
+                (RuleKind::Range { min, max }, _) if is_number(scalar) => {
+                    let in_range = compare(scalar, *min).is_some_and(Ordering::is_ge)
+                        && compare(scalar, *max).is_some_and(Ordering::is_le);
+                    if !in_range {
+                        let found = ScalarValue::from(scalar);
+                        let message =
+                            format!("must be between {} and {}, found {}", min, max, found);
+                        self.violation(path, message);
+                    }
+                }
+                (RuleKind::NonEmpty, Scalar::Str("")) => {
+                    self.violation(path, "must not be empty".to_string());
+                }
+                (RuleKind::Str(message, predicate), Scalar::Str(v)) if !predicate(v) => {
+                    self.violation(path, message.clone());
+                }
+                #[cfg(feature = "regex")]
+                (RuleKind::Regex(regex), Scalar::Str(v)) if !regex.is_match(v) => {
+                    self.violation(path, format!("must match `{}`", regex));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check(&self, _path: &Path, _scalar: Scalar) -> Result<(), String> {
+        self.enforce()
+    }
+
+    fn check_exit(&self, _path: &Path) -> Result<(), String> {
+        self.enforce()
+    }
+
+    fn exit(&self, path: &Path) {
+        let frame = match self.frames.borrow_mut().pop() {
+            Some(frame) => frame,
+            None => return,
+        };
+        for rule in self.matching(path) {
+            match rule {
+                RuleKind::NonEmpty if frame.len == 0 => {
+                    self.violation(path, "must not be empty".to_string());
+                }
+                RuleKind::Exclusive(keys) => {
+                    let present: Vec<&String> =
+                        keys.iter().filter(|key| frame.keys.contains(key)).collect();
+                    if present.len() > 1 {
+                        let mut message = String::new();
+                        for (i, key) in present.iter().enumerate() {
+                            if i > 0 {
+                                message += if i + 1 == present.len() {
+                                    " and "
+                                } else {
+                                    ", "
+                                };
+                            }
+                            message += &format!("`{}`", key);
+                        }
+                        message += " are mutually exclusive";
+                        self.violation(path, message);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn is_number(scalar: Scalar) -> bool {
+    matches!(
+        scalar,
+        Scalar::I64(_) | Scalar::I128(_) | Scalar::U64(_) | Scalar::U128(_) | Scalar::F64(_),
+    )
+}
+
+// Compares a number with a bound. Integers are compared exactly, rather than
+// after rounding them to the nearest float. None if either is NaN.
+fn compare(scalar: Scalar, bound: f64) -> Option<Ordering> {
+    match scalar {
+        Scalar::I64(v) => compare_i128(i128::from(v), bound),
+        Scalar::I128(v) => compare_i128(v, bound),
+        Scalar::U64(v) => compare_u128(u128::from(v), bound),
+        Scalar::U128(v) => compare_u128(v, bound),
+        Scalar::F64(v) => v.partial_cmp(&bound),
+        _ => None,
+    }
+}
+
+// Rounding to the nearest float preserves strict inequalities between an
+// integer and a float. When the rounded integer equals the float, the float is
+// itself an integer, and is compared as one.
+fn compare_i128(v: i128, bound: f64) -> Option<Ordering> {
+    match (v as f64).partial_cmp(&bound)? {
+        // 2^127, one more than i128::MAX.
+        Ordering::Equal if bound >= 170141183460469231731687303715884105728.0 => {
+            Some(Ordering::Less)
+        }
+        Ordering::Equal => Some(v.cmp(&(bound as i128))),
+        ordering => Some(ordering),
+    }
+}
+
+fn compare_u128(v: u128, bound: f64) -> Option<Ordering> {
+    match (v as f64).partial_cmp(&bound)? {
+        // 2^128, one more than u128::MAX.
+        Ordering::Equal if bound >= 340282366920938463463374607431768211456.0 => {
+            Some(Ordering::Less)
+        }
+        Ordering::Equal => Some(v.cmp(&(bound as u128))),
+        ordering => Some(ordering),
+    }
+}
@@ -0,0 +1,450 @@
+use crate::hook::Hook;
+use crate::path::Path;
+use crate::ser::serialize_tracked;
+use crate::{Error, Scalar};
+use alloc::string::{String, ToString as _};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt::{self, Display};
+use serde::ser::{self, Serialize, StdError};
+
+/// Lists every scalar in a value together with its path, like
+/// `dependencies.serde.version = "1.0"`, without serializing to any format.
+///
+/// Leaves are listed in the order in which the value serializes them. Unit
+/// variants are listed as the string of their name. Empty sequences, maps and
+/// structs contain no leaves, and map keys are not leaves of their own.
+///
+/// Fails if serializing the value fails, such as when a `RefCell` in it is
+/// mutably borrowed.
+///
+/// # Example
+///
+/// ```
+/// # use serde_derive::Serialize;
+/// #
+/// use serde_path_to_error::ScalarValue;
+///
+/// #[derive(Serialize)]
+/// struct Package {
+///     name: String,
+///     version: Option<u32>,
+///     keywords: Vec<String>,
+/// }
+///
+/// let package = Package {
+///     name: "demo".to_owned(),
+///     version: None,
+///     keywords: vec!["serde".to_owned()],
+/// };
+///
+/// let leaves: Vec<String> = serde_path_to_error::leaves(&package)
+///     .unwrap()
+///     .into_iter()
+///     .map(|(path, value)| format!("{} = {}", path, value))
+///     .collect();
+///
+/// assert_eq!(
+///     leaves,
+///     [r#"name = "demo""#, "version = None", r#"keywords[0] = "serde""#],
+/// );
+/// ```
+pub fn leaves<T>(value: &T) -> Result<Vec<(Path, ScalarValue)>, Error<WalkError>>
+where
+    T: ?Sized + Serialize,
+{
+    let collect = Collect {
+        leaves: RefCell::new(Vec::new()),
+    };
+    walk(value, &collect)?;
+    Ok(collect.leaves.into_inner())
+}
+
+// Serializes the value to nowhere, reporting to the given hook.
+pub(crate) fn walk<T>(value: &T, hook: &dyn Hook) -> Result<(), Error<WalkError>>
+where
+    T: ?Sized + Serialize,
+{
+    serialize_tracked(Discard, Some(hook), |serializer| {
+        value.serialize(serializer)
+    })
+}
+
+struct Collect {
+    leaves: RefCell<Vec<(Path, ScalarValue)>>,
+}
+
+impl Hook for Collect {
+    fn leaf(&self, path: &Path, scalar: Scalar) {
+        let leaf = (path.clone(), ScalarValue::from(scalar));
+        self.leaves.borrow_mut().push(leaf);
+    }
+}
+
+/// Scalar value found by [`leaves`]; an owned [`Scalar`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScalarValue {
+    Bool(bool),
+    I64(i64),
+    I128(i128),
+    U64(u64),
+    U128(u128),
+    F64(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    Unit,
+    None,
+}
+
+impl<'a> From<Scalar<'a>> for ScalarValue {
+    fn from(scalar: Scalar<'a>) -> Self {
+        match scalar {
+            Scalar::Bool(v) => ScalarValue::Bool(v),
+            Scalar::I64(v) => ScalarValue::I64(v),
+            Scalar::I128(v) => ScalarValue::I128(v),
+            Scalar::U64(v) => ScalarValue::U64(v),
+            Scalar::U128(v) => ScalarValue::U128(v),
+            Scalar::F64(v) => ScalarValue::F64(v),
+            Scalar::Char(v) => ScalarValue::Char(v),
+            Scalar::Str(v) => ScalarValue::Str(v.to_string()),
+            Scalar::Bytes(v) => ScalarValue::Bytes(v.to_vec()),
+            Scalar::Unit => ScalarValue::Unit,
+            Scalar::None => ScalarValue::None,
+        }
+    }
+}
+
+/// Displays numbers and booleans as is, characters, strings and bytes as
+/// quoted literals, and the unit value and `None` as `()` and `None`.
+impl Display for ScalarValue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScalarValue::Bool(v) => write!(formatter, "{}", v),
+            ScalarValue::I64(v) => write!(formatter, "{}", v),
+            ScalarValue::I128(v) => write!(formatter, "{}", v),
+            ScalarValue::U64(v) => write!(formatter, "{}", v),
+            ScalarValue::U128(v) => write!(formatter, "{}", v),
+            ScalarValue::F64(v) => write!(formatter, "{}", v),
+            ScalarValue::Char(v) => write!(formatter, "{:?}", v),
+            ScalarValue::Str(v) => write!(formatter, "{:?}", v),
+            ScalarValue::Bytes(v) => {
+                formatter.write_str("b\"")?;
+                for byte in v {
+                    write!(formatter, "{}", core::ascii::escape_default(*byte))?;
+                }
+                formatter.write_str("\"")
+            }
+            ScalarValue::Unit => formatter.write_str("()"),
+            ScalarValue::None => formatter.write_str("None"),
+        }
+    }
+}
+
+/// Error from a value's `Serialize` impl while walking it.
+#[derive(Clone, Debug)]
+pub struct WalkError {
+    message: String,
+}
+
+impl Display for WalkError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl StdError for WalkError {}
+
+impl ser::Error for WalkError {
+    fn custom<T: Display>(msg: T) -> Self {
+        WalkError {
+            message: msg.to_string(),
+        }
+    }
+}
+
+// Serializer that produces no output.
+pub(crate) struct Discard;
+
+impl ser::Serializer for Discard {
+    type Ok = ();
+    type Error = WalkError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, _v: bool) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_i128(self, _v: i128) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_u128(self, _v: u128) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_char(self, _v: char) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, WalkError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, WalkError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, WalkError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, WalkError> {
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, WalkError> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, WalkError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, WalkError> {
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for Discard {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(Discard)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Discard {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(Discard)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Discard {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(Discard)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Discard {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(Discard)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Discard {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        key.serialize(Discard)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(Discard)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Discard {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(Discard)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Discard {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), WalkError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(Discard)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
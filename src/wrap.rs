@@ -1,21 +1,21 @@
-use crate::{Chain, Track};
+use crate::{Chain, Tracker};
 
 // Wrapper that attaches context to a `Visitor`, `SeqAccess` or `EnumAccess`.
 pub struct Wrap<'a, 'b, X> {
     pub(crate) delegate: X,
     pub(crate) chain: &'a Chain<'a>,
-    pub(crate) track: &'b Track,
+    pub(crate) track: Tracker<'b>,
 }
 
 // Wrapper that attaches context to a `VariantAccess`.
 pub struct WrapVariant<'a, 'b, X> {
     pub(crate) delegate: X,
     pub(crate) chain: Chain<'a>,
-    pub(crate) track: &'b Track,
+    pub(crate) track: Tracker<'b>,
 }
 
 impl<'a, 'b, X> Wrap<'a, 'b, X> {
-    pub(crate) fn new(delegate: X, chain: &'a Chain<'a>, track: &'b Track) -> Self {
+    pub(crate) fn new(delegate: X, chain: &'a Chain<'a>, track: Tracker<'b>) -> Self {
         Wrap {
             delegate,
             chain,
@@ -25,7 +25,7 @@ impl<'a, 'b, X> Wrap<'a, 'b, X> {
 }
 
 impl<'a, 'b, X> WrapVariant<'a, 'b, X> {
-    pub(crate) fn new(delegate: X, chain: Chain<'a>, track: &'b Track) -> Self {
+    pub(crate) fn new(delegate: X, chain: Chain<'a>, track: Tracker<'b>) -> Self {
         WrapVariant {
             delegate,
             chain,
@@ -0,0 +1,214 @@
+#![cfg(feature = "std")]
+#![allow(dead_code)]
+
+use serde::de::{Deserialize, Deserializer, IgnoredAny, IntoDeserializer};
+use serde_derive::Deserialize;
+use std::cell::RefCell;
+use std::collections::BTreeMap as Map;
+use std::panic;
+use std::thread;
+
+thread_local! {
+    static SEEN: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Probe;
+
+impl<'de> Deserialize<'de> for Probe {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path = serde_path_to_error::current_path().map(|path| path.to_string());
+        SEEN.with(|seen| seen.borrow_mut().push(path.unwrap_or_default()));
+        IgnoredAny::deserialize(deserializer)?;
+        Ok(Probe)
+    }
+}
+
+#[test]
+fn test_current_path() {
+    #[derive(Deserialize)]
+    struct Package {
+        root: Probe,
+        dependencies: Map<String, Dependency>,
+    }
+
+    #[derive(Deserialize)]
+    struct Dependency {
+        version: Probe,
+        features: Vec<Probe>,
+        source: Option<Source>,
+    }
+
+    #[derive(Deserialize)]
+    enum Source {
+        Git { rev: Probe },
+    }
+
+    let j = r#"{
+        "root": null,
+        "dependencies": {
+            "serde": {
+                "version": "1.0",
+                "features": ["derive", "rc"],
+                "source": {"Git": {"rev": "abc"}}
+            }
+        }
+    }"#;
+
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let _: Package = serde_path_to_error::deserialize(de).unwrap();
+
+    let seen = SEEN.with(RefCell::take);
+    assert_eq!(
+        seen,
+        [
+            "root",
+            "dependencies.serde.version",
+            "dependencies.serde.features[0]",
+            "dependencies.serde.features[1]",
+            "dependencies.serde.source.Git.rev",
+        ],
+    );
+    assert!(serde_path_to_error::current_path().is_none());
+}
+
+// Deserializes an embedded JSON document, first through this crate and then
+// directly, and finally probes the path of its own value again.
+struct Embedded;
+
+impl<'de> Deserialize<'de> for Embedded {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let jd = &mut serde_json::Deserializer::from_str(&s);
+        let _: Vec<Probe> = serde_path_to_error::deserialize(jd).unwrap();
+        let _: Vec<Probe> = serde_json::from_str(&s).unwrap();
+        Probe::deserialize(IntoDeserializer::<D::Error>::into_deserializer(()))?;
+        Ok(Embedded)
+    }
+}
+
+#[test]
+fn test_nested_document() {
+    #[derive(Deserialize)]
+    struct Envelope {
+        payload: Embedded,
+    }
+
+    let j = r#"{"payload": "[1, 2]"}"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let _: Envelope = serde_path_to_error::deserialize(de).unwrap();
+
+    let seen = SEEN.with(RefCell::take);
+    assert_eq!(
+        seen,
+        [
+            // Through this crate: relative to the nested document.
+            "[0]", "[1]",
+            // Directly through serde_json: invisible to the wrapper, so the
+            // path of the enclosing value.
+            "payload", "payload", // Back in the enclosing value.
+            "payload",
+        ],
+    );
+}
+
+struct Panics;
+
+impl<'de> Deserialize<'de> for Panics {
+    fn deserialize<D>(_deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        panic!("deserialize panicked");
+    }
+}
+
+#[test]
+fn test_unwind() {
+    #[derive(Deserialize)]
+    struct Outer {
+        inner: Inner,
+    }
+
+    struct Inner;
+
+    impl<'de> Deserialize<'de> for Inner {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let result = panic::catch_unwind(|| {
+                let jd = &mut serde_json::Deserializer::from_str("[null]");
+                serde_path_to_error::deserialize::<_, Vec<Panics>>(jd)
+            });
+            assert!(result.is_err());
+            // The scopes of the nested document were unwound, leaving this
+            // value's own as the current one.
+            let path = serde_path_to_error::current_path().unwrap();
+            assert_eq!(path.to_string(), "inner");
+            IgnoredAny::deserialize(deserializer)?;
+            Ok(Inner)
+        }
+    }
+
+    let de = &mut serde_json::Deserializer::from_str(r#"{"inner": 0}"#);
+    let _: Outer = serde_path_to_error::deserialize(de).unwrap();
+    assert!(serde_path_to_error::current_path().is_none());
+
+    let result = panic::catch_unwind(|| {
+        let de = &mut serde_json::Deserializer::from_str(r#"{"inner": [null]}"#);
+        let _: Map<String, Vec<Panics>> = serde_path_to_error::deserialize(de).unwrap();
+    });
+    assert!(result.is_err());
+    assert!(serde_path_to_error::current_path().is_none());
+}
+
+#[test]
+fn test_other_thread() {
+    struct Spawns;
+
+    impl<'de> Deserialize<'de> for Spawns {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let elsewhere = thread::spawn(serde_path_to_error::current_path)
+                .join()
+                .unwrap();
+            assert!(elsewhere.is_none());
+            assert!(serde_path_to_error::current_path().is_some());
+            IgnoredAny::deserialize(deserializer)?;
+            Ok(Spawns)
+        }
+    }
+
+    let de = &mut serde_json::Deserializer::from_str("[0]");
+    let _: Vec<Spawns> = serde_path_to_error::deserialize(de).unwrap();
+}
+
+#[test]
+fn test_manual_wrapper() {
+    let j = r#"{"a": [null]}"#;
+
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let mut track = serde_path_to_error::Track::new();
+    let pd = serde_path_to_error::Deserializer::new(de, &mut track);
+    let _ = Map::<String, Vec<Probe>>::deserialize(pd).unwrap();
+
+    let de = &mut serde_json::Deserializer::from_str("null");
+    let mut track = serde_path_to_error::Track::new();
+    let pd = serde_path_to_error::Deserializer::new(de, &mut track);
+    let _ = Probe::deserialize(pd).unwrap();
+
+    // Unlike an entry point, which also covers the root.
+    let de = &mut serde_json::Deserializer::from_str("null");
+    let _: Probe = serde_path_to_error::deserialize(de).unwrap();
+
+    let seen = SEEN.with(RefCell::take);
+    assert_eq!(seen, ["a[0]", "", "."]);
+}
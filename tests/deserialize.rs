@@ -226,3 +226,44 @@ fn test_map_nonstring_key() {
 
     test::<Map<i32, Dependency>>(j, "100.version");
 }
+
+#[test]
+fn test_seed() {
+    #[derive(Deserialize, Debug)]
+    struct Package {
+        name: String,
+    }
+
+    let j = r#"[{"name": "demo"}, {"name": 1}]"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let seed = std::marker::PhantomData::<Vec<Package>>;
+    let result = serde_path_to_error::deserialize_seed(seed, de);
+    let path = result.unwrap_err().path().to_string();
+    assert_eq!(path, "[1].name");
+}
+
+#[test]
+fn test_in_place() {
+    let mut place = vec![String::with_capacity(64), String::new()];
+    let ptr = place[0].as_ptr();
+
+    let j = r#"["a", "b", "c"]"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    serde_path_to_error::deserialize_in_place(de, &mut place).unwrap();
+    assert_eq!(place, ["a", "b", "c"]);
+    assert_eq!(place[0].as_ptr(), ptr);
+
+    let mut place: Map<String, Vec<Option<String>>> = Map::new();
+    let j = r#"{"k": [null, "x", 1]}"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let result = serde_path_to_error::deserialize_in_place(de, &mut place);
+    let path = result.unwrap_err().path().to_string();
+    assert_eq!(path, "k[2]");
+
+    let mut place = vec![vec![0u8; 4], vec![0u8; 4]];
+    let j = r#"[[1, 2], [3, 256]]"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let result = serde_path_to_error::deserialize_in_place(de, &mut place);
+    let path = result.unwrap_err().path().to_string();
+    assert_eq!(path, "[1][1]");
+}
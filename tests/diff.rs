@@ -0,0 +1,158 @@
+use serde::Serialize;
+use serde_derive::Serialize;
+use serde_path_to_error::{Change, ScalarValue, Shape};
+use std::collections::BTreeMap as Map;
+
+fn diff<A: Serialize, B: Serialize>(old: &A, new: &B) -> Vec<String> {
+    serde_path_to_error::diff(old, new)
+        .unwrap()
+        .iter()
+        .map(Change::to_string)
+        .collect()
+}
+
+#[derive(Serialize, Clone)]
+struct Package {
+    name: String,
+    license: Option<String>,
+    keywords: Vec<String>,
+    dependencies: Map<String, Dependency>,
+}
+
+#[derive(Serialize, Clone)]
+enum Dependency {
+    Path(String),
+    Registry { version: String },
+    Workspace,
+}
+
+fn package() -> Package {
+    let mut dependencies = Map::new();
+    dependencies.insert(
+        "serde".to_owned(),
+        Dependency::Registry {
+            version: "1.0".to_owned(),
+        },
+    );
+    dependencies.insert("local".to_owned(), Dependency::Path("../local".to_owned()));
+    Package {
+        name: "demo".to_owned(),
+        license: None,
+        keywords: vec!["a".to_owned(), "b".to_owned()],
+        dependencies,
+    }
+}
+
+#[test]
+fn test_unchanged() {
+    assert!(diff(&package(), &package()).is_empty());
+}
+
+#[test]
+fn test_scalar() {
+    let mut new = package();
+    new.name = "renamed".to_owned();
+    new.dependencies.insert(
+        "serde".to_owned(),
+        Dependency::Registry {
+            version: "1.1".to_owned(),
+        },
+    );
+    assert_eq!(
+        diff(&package(), &new),
+        [
+            r#"name: "demo" -> "renamed""#,
+            r#"dependencies.serde.version: "1.0" -> "1.1""#,
+        ],
+    );
+}
+
+#[test]
+fn test_added_removed() {
+    let mut new = package();
+    new.keywords.push("c".to_owned());
+    new.dependencies.remove("local");
+    new.dependencies
+        .insert("log".to_owned(), Dependency::Workspace);
+    assert_eq!(
+        diff(&package(), &new),
+        [
+            r#"keywords[2]: added "c""#,
+            r#"dependencies.local: removed Path(..)"#,
+            r#"dependencies.log: added "Workspace""#,
+        ],
+    );
+
+    let mut new = package();
+    new.keywords.clear();
+    assert_eq!(
+        diff(&package(), &new),
+        [r#"keywords[0]: removed "a""#, r#"keywords[1]: removed "b""#],
+    );
+}
+
+#[test]
+fn test_shape() {
+    let mut new = package();
+    new.license = Some("MIT".to_owned());
+    new.dependencies
+        .insert("local".to_owned(), Dependency::Workspace);
+    new.dependencies
+        .insert("serde".to_owned(), Dependency::Path("../serde".to_owned()));
+    assert_eq!(
+        diff(&package(), &new),
+        [
+            r#"license: None -> "MIT""#,
+            r#"dependencies.local: Path(..) -> "Workspace""#,
+            r#"dependencies.serde: Registry(..) -> Path(..)"#,
+        ],
+    );
+
+    let changes = serde_path_to_error::diff(&vec![1, 2], &Map::from([("k", 1)])).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path().to_string(), ".");
+    match &changes[0] {
+        Change::Modified { old, new, .. } => {
+            assert_eq!(*old, Shape::Seq { len: 2 });
+            assert_eq!(*new, Shape::Map { len: 1 });
+        }
+        change => panic!("unexpected change: {}", change),
+    }
+}
+
+#[test]
+fn test_non_string_keys() {
+    let old = Map::from([(1, "a"), (2, "b")]);
+    let new = Map::from([(1, "a"), (2, "c")]);
+    let changes = serde_path_to_error::diff(&old, &new).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path().to_string(), "2");
+    match &changes[0] {
+        Change::Modified { new, .. } => {
+            assert_eq!(*new, Shape::Scalar(ScalarValue::Str("c".to_owned())));
+        }
+        change => panic!("unexpected change: {}", change),
+    }
+
+    // Keys with no string form cannot be matched up by path.
+    let old = Map::from([((1, 2), "a")]);
+    let new = Map::from([((1, 2), "b")]);
+    let err = serde_path_to_error::diff(&old, &new).unwrap_err();
+    assert_eq!(err.path().to_string(), "?");
+    assert!(err
+        .inner()
+        .to_string()
+        .starts_with("cannot compare map entries"));
+}
+
+#[test]
+fn test_many_entries() {
+    let old: Map<String, u32> = (0..1000).map(|i| (i.to_string(), i)).collect();
+    let mut new = old.clone();
+    new.remove("500");
+    new.insert("1000".to_owned(), 1000);
+    assert_eq!(
+        diff(&old, &new),
+        [r#"500: removed 500"#, r#"1000: added 1000"#],
+    );
+}
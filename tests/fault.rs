@@ -0,0 +1,173 @@
+use serde::Serialize;
+use serde_derive::{Deserialize, Serialize};
+use serde_path_to_error::Fault;
+
+fn ser<T>(fault: &Fault, value: &T) -> Result<String, String>
+where
+    T: ?Sized + Serialize,
+{
+    let mut out = Vec::new();
+    let ser = &mut serde_json::Serializer::new(&mut out);
+    match fault.serialize(value, ser) {
+        Ok(()) => Ok(String::from_utf8(out).unwrap()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn de(fault: &Fault, j: &str) -> Result<Package, String> {
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    fault.deserialize(jd).map_err(|err| err.to_string())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Package {
+    name: String,
+    version: Option<String>,
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Dependency {
+    name: String,
+}
+
+const J: &str =
+    r#"{"name":"demo","version":"1.0","dependencies":[{"name":"serde"},{"name":"log"}]}"#;
+
+fn package() -> Package {
+    serde_json::from_str(J).unwrap()
+}
+
+#[test]
+fn test_serialize_at_path() {
+    let fault = Fault::at_path("dependencies[1].name").unwrap();
+    assert_eq!(
+        ser(&fault, &package()).unwrap_err(),
+        "dependencies[1].name: injected fault",
+    );
+
+    let fault = Fault::at_path("nothing").unwrap();
+    assert_eq!(ser(&fault, &package()).unwrap(), J);
+}
+
+#[test]
+fn test_deserialize_at_path() {
+    let fault = Fault::at_path("**[*]").unwrap().message("boom");
+    assert_eq!(
+        de(&fault, J).unwrap_err(),
+        "dependencies[0]: boom at line 1 column 47"
+    );
+
+    let fault = Fault::at_path("version").unwrap();
+    assert_eq!(
+        de(&fault, J).unwrap_err(),
+        "version: injected fault at line 1 column 25",
+    );
+
+    let fault = Fault::at_path("nothing").unwrap();
+    assert_eq!(de(&fault, J).unwrap().dependencies.len(), 2);
+}
+
+#[test]
+fn test_at_node() {
+    // The root, `name`, `version`, the string inside of `Some`,
+    // `dependencies`, then its first element.
+    let fault = Fault::at_node(0);
+    assert_eq!(ser(&fault, &package()).unwrap_err(), "injected fault");
+    assert_eq!(de(&fault, J).unwrap_err(), "injected fault");
+
+    let fault = Fault::at_node(3);
+    assert_eq!(
+        ser(&fault, &package()).unwrap_err(),
+        "version: injected fault"
+    );
+    assert_eq!(
+        de(&fault, J).unwrap_err(),
+        "version: injected fault at line 1 column 25",
+    );
+
+    let fault = Fault::at_node(5);
+    assert_eq!(
+        ser(&fault, &package()).unwrap_err(),
+        "dependencies[0]: injected fault",
+    );
+    assert_eq!(
+        de(&fault, J).unwrap_err(),
+        "dependencies[0]: injected fault at line 1 column 47",
+    );
+
+    let fault = Fault::at_node(100);
+    assert_eq!(ser(&fault, &package()).unwrap(), J);
+    assert!(de(&fault, J).is_ok());
+}
+
+#[test]
+fn test_reusable() {
+    let fault = Fault::at_node(1);
+    for _ in 0..2 {
+        assert_eq!(ser(&fault, &package()).unwrap_err(), "name: injected fault");
+        assert_eq!(
+            de(&fault, J).unwrap_err(),
+            "name: injected fault at line 1 column 8",
+        );
+    }
+}
+
+#[test]
+fn test_option_and_newtype_count() {
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Release {
+        id: Id,
+        notes: Option<Id>,
+        kind: Kind,
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Id(u32);
+
+    #[derive(Serialize, Deserialize, Debug)]
+    enum Kind {
+        Patch(u32),
+    }
+
+    let release = Release {
+        id: Id(1),
+        notes: Some(Id(2)),
+        kind: Kind::Patch(3),
+    };
+    let j = r#"{"id":1,"notes":2,"kind":{"Patch":3}}"#;
+
+    // The root, then `id` and its contents, `notes`, its contents and their
+    // contents, then `kind` and its contents. Each counts at the path of the
+    // field, except that deserialization names the variant of an enum.
+    let expected = [
+        (1, "id", "id"),
+        (2, "id", "id"),
+        (3, "notes", "notes"),
+        (4, "notes", "notes"),
+        (5, "notes", "notes"),
+        (6, "kind", "kind"),
+        (7, "kind", "kind.Patch"),
+    ];
+    for (index, ser_path, de_path) in expected {
+        let fault = Fault::at_node(index);
+        assert_eq!(
+            ser(&fault, &release).unwrap_err(),
+            format!("{}: injected fault", ser_path),
+            "node {}",
+            index,
+        );
+        let jd = &mut serde_json::Deserializer::from_str(j);
+        let err = fault.deserialize::<_, Release>(jd).unwrap_err();
+        assert_eq!(err.path().to_string(), de_path, "node {}", index);
+    }
+
+    let fault = Fault::at_node(8);
+    assert_eq!(ser(&fault, &release).unwrap(), j);
+}
+
+#[test]
+fn test_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Fault>();
+}
@@ -0,0 +1,211 @@
+#![cfg(feature = "serde_json")]
+#![allow(dead_code)]
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::json;
+use serde_path_to_error::json;
+use std::cell::RefCell;
+use std::collections::BTreeMap as Map;
+
+#[derive(Deserialize, Debug)]
+struct Package {
+    name: String,
+    dependencies: Map<String, Dependency>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Dependency {
+    version: String,
+}
+
+#[test]
+fn test_from() {
+    let j = r#"{"name": "demo", "dependencies": {"serde": {"version": 1}}}"#;
+    let expected = "dependencies.serde.version";
+
+    let err = json::from_str::<Package>(j).unwrap_err();
+    assert_eq!(err.path().to_string(), expected);
+
+    let err = json::from_slice::<Package>(j.as_bytes()).unwrap_err();
+    assert_eq!(err.path().to_string(), expected);
+
+    let err = json::from_reader::<_, Package>(j.as_bytes()).unwrap_err();
+    assert_eq!(err.path().to_string(), expected);
+
+    let value: serde_json::Value = serde_json::from_str(j).unwrap();
+    let err = json::from_value::<Package>(value).unwrap_err();
+    assert_eq!(err.path().to_string(), expected);
+}
+
+#[test]
+fn test_trailing_characters() {
+    let j = r#"{"name": "demo", "dependencies": {}} {"#;
+
+    let err = json::from_str::<Package>(j).unwrap_err();
+    assert_eq!(err.path().iter().count(), 0);
+    assert_eq!(err.to_string(), "trailing characters at line 1 column 38");
+
+    assert!(json::from_slice::<Package>(j.as_bytes()).is_err());
+    assert!(json::from_reader::<_, Package>(j.as_bytes()).is_err());
+
+    let package = json::from_str::<Package>(r#"{"name": "demo", "dependencies": {}}  "#).unwrap();
+    assert_eq!(package.name, "demo");
+}
+
+#[test]
+fn test_to() {
+    #[derive(Serialize)]
+    struct Outer<'a> {
+        k: Inner<'a>,
+    }
+
+    #[derive(Serialize)]
+    struct Inner<'a> {
+        refcell: &'a RefCell<String>,
+    }
+
+    let refcell = RefCell::new(String::from("x"));
+    let value = Outer {
+        k: Inner { refcell: &refcell },
+    };
+
+    assert_eq!(json::to_string(&value).unwrap(), r#"{"k":{"refcell":"x"}}"#);
+    assert_eq!(
+        json::to_value(&value).unwrap(),
+        json!({"k": {"refcell": "x"}})
+    );
+
+    let _borrowed = refcell.borrow_mut();
+
+    let err = json::to_string(&value).unwrap_err();
+    assert_eq!(err.path().to_string(), "k.refcell");
+
+    let err = json::to_writer(Vec::new(), &value).unwrap_err();
+    assert_eq!(err.path().to_string(), "k.refcell");
+
+    let err = json::to_value(&value).unwrap_err();
+    assert_eq!(err.path().to_string(), "k.refcell");
+}
+
+#[test]
+fn test_value_lookup() {
+    #[derive(Deserialize, Debug)]
+    enum Source {
+        Git { rev: String },
+        Registry,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Manifest {
+        sources: Map<String, Vec<Source>>,
+    }
+
+    let mut value = json!({
+        "sources": {
+            "a/b~c": [{"Git": {"rev": "main"}}, {"Git": {"rev": 1}}],
+        },
+    });
+
+    let err = json::from_value::<Manifest>(value.clone()).unwrap_err();
+    let path = err.path();
+    assert_eq!(path.to_string(), "sources.a/b~c[1].Git.rev");
+    assert_eq!(path.get(&value), Some(&json!(1)));
+
+    let pointer = path.to_json_pointer().unwrap();
+    assert_eq!(pointer, "/sources/a~1b~0c/1/Git/rev");
+    assert_eq!(value.pointer(&pointer), Some(&json!(1)));
+
+    *path.get_mut(&mut value).unwrap() = json!("v1");
+    let manifest = json::from_value::<Manifest>(value.clone()).unwrap();
+    assert_eq!(manifest.sources["a/b~c"].len(), 2);
+
+    let value = json!({"sources": {"x": ["Registry", "Local"]}});
+    let err = json::from_value::<Manifest>(value.clone()).unwrap_err();
+    assert_eq!(err.path().to_string(), "sources.x[1]");
+    assert_eq!(err.path().get(&value), Some(&json!("Local")));
+    assert_eq!(err.path().get(&json!({"sources": []})), None);
+}
+
+#[test]
+fn test_documents() {
+    #[derive(Deserialize, Debug)]
+    struct Record {
+        id: u64,
+        items: Vec<Map<String, u64>>,
+    }
+
+    let ndjson = "{\"id\": 1, \"items\": []}\n{\"id\": 2, \"items\": [{}, {\"n\": -1}]}\n{\"id\": 3, \"items\": []}\n";
+
+    let results: Vec<_> = json::documents_from_str::<Record>(ndjson).collect();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().id, 1);
+    let err = results[1].as_ref().unwrap_err();
+    assert_eq!(err.index(), 1);
+    assert_eq!(err.path().to_string(), "items[1].n");
+    assert_eq!(
+        err.to_string(),
+        "#1.items[1].n: invalid value: integer `-1`, expected u64 at line 1 column 32",
+    );
+    assert_eq!(results[2].as_ref().unwrap().id, 3);
+
+    #[cfg(feature = "json-span")]
+    {
+        let line = ndjson.lines().nth(err.index()).unwrap();
+        let span = serde_path_to_error::locate_json(line, err.path()).unwrap();
+        assert_eq!(&line[span.start..span.end], "-1");
+    }
+
+    let ids: Vec<_> = json::documents_from_reader::<_, Record>(ndjson.as_bytes())
+        .map(|record| record.map(|record| record.id).map_err(|err| err.index()))
+        .collect();
+    assert_eq!(ids, [Ok(1), Err(1), Ok(3)]);
+
+    let ids: Vec<_> =
+        json::documents_from_slice::<Record>(b" \r\n{\"id\": 7, \"items\": []}\r\n  ")
+            .map(|record| record.unwrap().id)
+            .collect();
+    assert_eq!(ids, [7]);
+}
+
+#[test]
+fn test_documents_syntax_error() {
+    let ndjson = "[1, 2]\n[3, \n[4]\n{\"a\": 5} {}\n";
+    let results: Vec<_> = json::documents_from_str::<Vec<u64>>(ndjson)
+        .map(|result| result.map_err(|err| (err.index(), err.error().inner().to_string())))
+        .collect();
+    assert_eq!(
+        results,
+        [
+            Ok(vec![1, 2]),
+            Err((1, "EOF while parsing a value at line 1 column 4".to_owned())),
+            Ok(vec![4]),
+            Err((
+                3,
+                "invalid type: map, expected a sequence at line 1 column 0".to_owned()
+            )),
+        ],
+    );
+
+    let err = json::documents_from_str::<Vec<u64>>(ndjson)
+        .nth(1)
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "#1: EOF while parsing a value at line 1 column 4"
+    );
+}
+
+#[test]
+fn test_documents_generic() {
+    let values = vec![json!([1, 2]), json!([3, "x"]), json!([])];
+    let results: Vec<Result<Vec<u64>, _>> = serde_path_to_error::documents(values).collect();
+    assert_eq!(results.len(), 3);
+    let err = results[1].as_ref().unwrap_err();
+    assert_eq!((err.index(), err.path().to_string()), (1, "[1]".to_owned()));
+    assert_eq!(
+        err.to_string(),
+        "#1[1]: invalid type: string \"x\", expected u64",
+    );
+    assert_eq!(results[2].as_ref().unwrap(), &Vec::<u64>::new());
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "json-span")]
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use serde_derive::Deserialize;
+use serde_path_to_error::{locate_json, locate_json_key, Path, Span};
+use std::collections::BTreeMap as Map;
+
+fn path_of<'de, T: Deserialize<'de>>(j: &'de str) -> Path {
+    let de = &mut serde_json::Deserializer::from_str(j);
+    match serde_path_to_error::deserialize::<_, T>(de) {
+        Ok(_) => panic!("expected failure"),
+        Err(err) => err.path().clone(),
+    }
+}
+
+fn text(j: &str, span: Span) -> &str {
+    &j[span.start..span.end]
+}
+
+#[test]
+fn test_nested() {
+    #[derive(Deserialize)]
+    struct Package {
+        name: String,
+        dependencies: Map<String, Dependency>,
+    }
+
+    #[derive(Deserialize)]
+    struct Dependency {
+        version: String,
+    }
+
+    let j = r#"{
+        "name": "demo",
+        "dependencies": {
+            "serde": {"version": "1.0"},
+            "syn": {
+                "version": [1, {"x": "]"}]
+            }
+        }
+    }"#;
+
+    let path = path_of::<Package>(j);
+    assert_eq!(path.to_string(), "dependencies.syn.version");
+
+    let value = locate_json(j, &path).unwrap();
+    assert_eq!(text(j, value), r#"[1, {"x": "]"}]"#);
+
+    let key = locate_json_key(j, &path).unwrap();
+    assert_eq!(text(j, key), r#""version""#);
+    let location = key.line_column(j);
+    assert_eq!((location.line, location.column), (6, 17));
+}
+
+#[test]
+fn test_missing_field() {
+    #[derive(Deserialize)]
+    struct Package {
+        dependencies: Vec<Dependency>,
+    }
+
+    #[derive(Deserialize)]
+    struct Dependency {
+        name: String,
+        version: String,
+    }
+
+    let j = r#"{"dependencies": [{"name": "serde", "version": "1"}, {"name": "syn"}]}"#;
+
+    let path = path_of::<Package>(j);
+    assert_eq!(path.to_string(), "dependencies[1]");
+    let value = locate_json(j, &path).unwrap();
+    assert_eq!(text(j, value), r#"{"name": "syn"}"#);
+    assert_eq!(locate_json_key(j, &path), None);
+}
+
+#[test]
+fn test_escaped_key_and_enum() {
+    #[derive(Deserialize)]
+    enum Source {
+        Git { rev: u32 },
+    }
+
+    let j = "{\"caf\\u00e9\": {\"Git\": {\"rev\": \"x\"}}}";
+
+    let path = path_of::<Map<String, Source>>(j);
+    assert_eq!(path.to_string(), "café.Git.rev");
+    assert_eq!(text(j, locate_json(j, &path).unwrap()), "\"x\"");
+    assert_eq!(text(j, locate_json_key(j, &path).unwrap()), "\"rev\"");
+}
+
+#[test]
+fn test_not_found() {
+    #[derive(Deserialize)]
+    struct Package {
+        version: u32,
+    }
+
+    let path = path_of::<Package>(r#"{"version": "1"}"#);
+    assert_eq!(locate_json(r#"{"name": "demo"}"#, &path), None);
+    assert_eq!(locate_json("[1, 2]", &path), None);
+}
@@ -0,0 +1,139 @@
+use serde_derive::Serialize;
+use serde_path_to_error::ScalarValue;
+use std::cell::RefCell;
+use std::collections::BTreeMap as Map;
+
+fn leaves<T: serde::Serialize>(value: &T) -> Vec<String> {
+    serde_path_to_error::leaves(value)
+        .unwrap()
+        .into_iter()
+        .map(|(path, value)| format!("{} = {}", path, value))
+        .collect()
+}
+
+#[test]
+fn test_struct() {
+    #[derive(Serialize)]
+    struct Package {
+        name: String,
+        version: (u32, u32),
+        dependencies: Map<String, Dependency>,
+        keywords: Vec<String>,
+        license: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    enum Dependency {
+        Path(String),
+        Registry { version: String, optional: bool },
+        Workspace,
+    }
+
+    let mut dependencies = Map::new();
+    dependencies.insert("local".to_owned(), Dependency::Path("../local".to_owned()));
+    dependencies.insert(
+        "serde".to_owned(),
+        Dependency::Registry {
+            version: "1.0".to_owned(),
+            optional: false,
+        },
+    );
+    dependencies.insert("shared".to_owned(), Dependency::Workspace);
+
+    let package = Package {
+        name: "demo".to_owned(),
+        version: (1, 2),
+        dependencies,
+        keywords: Vec::new(),
+        license: Some("MIT".to_owned()),
+    };
+
+    assert_eq!(
+        leaves(&package),
+        [
+            r#"name = "demo""#,
+            "version[0] = 1",
+            "version[1] = 2",
+            r#"dependencies.local = "../local""#,
+            r#"dependencies.serde.version = "1.0""#,
+            "dependencies.serde.optional = false",
+            r#"dependencies.shared = "Workspace""#,
+            r#"license = "MIT""#,
+        ],
+    );
+}
+
+#[test]
+fn test_scalars() {
+    #[derive(Serialize)]
+    struct Unit;
+
+    let values = (
+        -1i8,
+        2u128,
+        0.5f32,
+        'x',
+        Bytes(b"a\n"),
+        (),
+        Unit,
+        None::<u8>,
+    );
+    let leaves = serde_path_to_error::leaves(&values).unwrap();
+    let scalars: Vec<ScalarValue> = leaves.into_iter().map(|(_path, value)| value).collect();
+    assert_eq!(
+        scalars,
+        [
+            ScalarValue::I64(-1),
+            ScalarValue::U128(2),
+            ScalarValue::F64(0.5),
+            ScalarValue::Char('x'),
+            ScalarValue::Bytes(b"a\n".to_vec()),
+            ScalarValue::Unit,
+            ScalarValue::Unit,
+            ScalarValue::None,
+        ],
+    );
+    assert_eq!(ScalarValue::Bytes(b"a\n".to_vec()).to_string(), r#"b"a\n""#);
+}
+
+#[test]
+fn test_root() {
+    assert_eq!(leaves(&7), [". = 7"]);
+}
+
+#[test]
+fn test_collect_str() {
+    struct Version(u32, u32);
+
+    impl serde::Serialize for Version {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(&format_args!("{}.{}", self.0, self.1))
+        }
+    }
+
+    let versions = [Version(1, 0), Version(0, 12)];
+    assert_eq!(leaves(&versions), [r#"[0] = "1.0""#, r#"[1] = "0.12""#]);
+}
+
+#[test]
+fn test_error() {
+    #[derive(Serialize)]
+    struct Outer<'a> {
+        k: Vec<&'a RefCell<u8>>,
+    }
+
+    let refcell = RefCell::new(0);
+    let outer = Outer { k: vec![&refcell] };
+    let _borrowed = refcell.borrow_mut();
+    let err = serde_path_to_error::leaves(&outer).unwrap_err();
+    assert_eq!(err.path().to_string(), "k[0]");
+    assert_eq!(err.inner().to_string(), "already mutably borrowed");
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> serde::Serialize for Bytes<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "std")]
+#![allow(dead_code)]
+
+use serde_derive::Deserialize;
+use std::collections::BTreeMap as Map;
+
+#[derive(Deserialize, Debug)]
+struct Package {
+    name: String,
+    dependencies: Map<String, Dependency>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Dependency {
+    version: String,
+    #[serde(default, deserialize_with = "serde_path_to_error::lenient_option")]
+    features: Option<Vec<String>>,
+}
+
+#[test]
+fn test_lenient() {
+    let j = r#"{
+        "name": "demo",
+        "dependencies": {
+            "serde": {
+                "version": "1.0",
+                "features": ["derive", 1]
+            },
+            "serde_json": {
+                "version": "1.0",
+                "features": ["raw_value"]
+            },
+            "itoa": {
+                "version": "1.0",
+                "features": null
+            }
+        }
+    }"#;
+
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let (package, warnings) = serde_path_to_error::deserialize_lenient::<_, Package>(de).unwrap();
+
+    assert_eq!(package.name, "demo");
+    assert!(package.dependencies["serde"].features.is_none());
+    assert_eq!(
+        package.dependencies["serde_json"].features.as_deref(),
+        Some(&["raw_value".to_owned()][..]),
+    );
+    assert!(package.dependencies["itoa"].features.is_none());
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].to_string(),
+        "dependencies.serde.features[1]: invalid type: integer `1`, expected a string",
+    );
+}
+
+#[test]
+fn test_not_lenient() {
+    let j = r#"{
+        "name": "demo",
+        "dependencies": {
+            "serde": {
+                "version": "1.0",
+                "features": ["derive", 1]
+            }
+        }
+    }"#;
+
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let result: Result<Package, _> = serde_path_to_error::deserialize(de);
+    let path = result.unwrap_err().path().to_string();
+    assert_eq!(path, "dependencies.serde.features[1]");
+}
+
+#[test]
+fn test_lenient_other_error() {
+    let j = r#"{
+        "name": "demo",
+        "dependencies": {
+            "serde": {
+                "features": ["derive", 1]
+            }
+        }
+    }"#;
+
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let err = serde_path_to_error::deserialize_lenient::<_, Package>(de).unwrap_err();
+    assert_eq!(err.path().to_string(), "dependencies.serde");
+}
+
+#[test]
+fn test_nested() {
+    #[derive(Deserialize, Debug)]
+    struct Outer {
+        #[serde(default, deserialize_with = "serde_path_to_error::lenient_option")]
+        inner: Option<Inner>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Inner {
+        #[serde(default, deserialize_with = "serde_path_to_error::lenient_option")]
+        x: Option<u32>,
+        y: u32,
+    }
+
+    let j = r#"{"inner": {"x": "bad", "y": 1}}"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let (outer, warnings) = serde_path_to_error::deserialize_lenient::<_, Outer>(de).unwrap();
+    let inner = outer.inner.unwrap();
+    assert!(inner.x.is_none());
+    assert_eq!(inner.y, 1);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].path().to_string(), "inner.x");
+
+    // When the outer value fails anyway, only it is reported.
+    let j = r#"{"inner": {"x": "bad", "y": "bad"}}"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let (outer, warnings) = serde_path_to_error::deserialize_lenient::<_, Outer>(de).unwrap();
+    assert!(outer.inner.is_none());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].path().to_string(), "inner.y");
+}
+
+#[test]
+fn test_inside_lossy() {
+    #[derive(Deserialize, Debug)]
+    struct Upload {
+        rows: serde_path_to_error::Lossy<Vec<Row>>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Row {
+        id: u32,
+        #[serde(default, deserialize_with = "serde_path_to_error::lenient_option")]
+        tag: Option<String>,
+    }
+
+    let j = r#"{"rows": [{"id": 1, "tag": 2}, {"id": "x"}]}"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let (upload, warnings) = serde_path_to_error::deserialize_lenient::<_, Upload>(de).unwrap();
+    assert_eq!(upload.rows.len(), 1);
+    assert!(upload.rows[0].tag.is_none());
+    assert_eq!(upload.rows.errors().len(), 1);
+    assert_eq!(upload.rows.errors()[0].path().to_string(), "rows[1].id");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].path().to_string(), "rows[0].tag");
+}
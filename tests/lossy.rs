@@ -0,0 +1,171 @@
+use serde::de::value::SeqDeserializer;
+use serde::de::value::{self, EnumAccessDeserializer, MapAccessDeserializer, MapDeserializer};
+use serde_derive::Deserialize;
+use serde_path_to_error::Lossy;
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Row {
+    id: u32,
+    tags: Vec<String>,
+}
+
+#[test]
+fn test_lossy_seq() {
+    let j = r#"[
+        {"id": 1, "tags": []},
+        {"id": 2, "tags": ["a", 1]},
+        {"id": -3, "tags": []},
+        {"id": 4, "tags": ["b"]}
+    ]"#;
+
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let (rows, errors) = serde_path_to_error::deserialize_lossy_seq::<_, Row>(de).unwrap();
+
+    let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+    assert_eq!(ids, [1, 4]);
+
+    let paths: Vec<String> = errors.iter().map(|err| err.path().to_string()).collect();
+    assert_eq!(paths, ["[1].tags[1]", "[2].id"]);
+}
+
+#[test]
+fn test_lossy_map() {
+    let j = r#"{
+        "serde": {"id": 1, "tags": []},
+        "serde_json": {"id": 2, "tags": {}},
+        "itoa": {"id": 3, "tags": ["fmt"]}
+    }"#;
+
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let (entries, errors) =
+        serde_path_to_error::deserialize_lossy_map::<_, String, Row>(de).unwrap();
+
+    let keys: Vec<&str> = entries.iter().map(|(key, _row)| key.as_str()).collect();
+    assert_eq!(keys, ["serde", "itoa"]);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path().to_string(), "serde_json.tags");
+}
+
+#[test]
+fn test_lossy_syntax_error() {
+    let j = r#"[
+        {"id": 1, "tags": []},
+        {"id": 2, "tags": [*]}
+    ]"#;
+
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let err = serde_path_to_error::deserialize_lossy_seq::<_, Row>(de).unwrap_err();
+    assert_eq!(err.path().to_string(), "[1].tags[0]");
+}
+
+#[derive(Deserialize)]
+struct Upload {
+    name: String,
+    rows: Lossy<Vec<Row>>,
+    owners: Lossy<BTreeMap<String, Row>>,
+}
+
+#[test]
+fn test_nested() {
+    let j = r#"{
+        "name": "batch",
+        "rows": [{"id": 1, "tags": []}, {"id": 2, "tags": [false]}, {"id": 3, "tags": []}],
+        "owners": {"dtolnay": {"id": 4, "tags": []}, "nobody": {"tags": []}}
+    }"#;
+
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let upload: Upload = serde_path_to_error::deserialize(de).unwrap();
+    assert_eq!(upload.name, "batch");
+
+    let ids: Vec<u32> = upload.rows.iter().map(|row| row.id).collect();
+    assert_eq!(ids, [1, 3]);
+    let owners: Vec<&str> = upload.owners.keys().map(String::as_str).collect();
+    assert_eq!(owners, ["dtolnay"]);
+
+    let paths: Vec<String> = upload
+        .rows
+        .errors()
+        .iter()
+        .chain(upload.owners.errors())
+        .map(|err| err.path().to_string())
+        .collect();
+    if cfg!(feature = "std") {
+        assert_eq!(paths, ["rows[1].tags[0]", "owners.nobody"]);
+    } else {
+        assert_eq!(paths, ["[1].tags[0]", "nobody"]);
+    }
+    assert_eq!(upload.owners.errors()[0].inner(), "missing field `id`",);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_span() {
+    use std::cell::Cell;
+    use std::io::{self, Read};
+
+    struct CountingReader<'a> {
+        inner: &'a [u8],
+        offset: &'a Cell<usize>,
+    }
+
+    impl<'a> Read for CountingReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.offset.set(self.offset.get() + n);
+            Ok(n)
+        }
+    }
+
+    let j = r#"{"name": "batch", "rows": [{"id": 1, "tags": []}, {"id": -2, "tags": []}], "owners": {}}"#;
+    let offset = Cell::new(0);
+    let reader = CountingReader {
+        inner: j.as_bytes(),
+        offset: &offset,
+    };
+    let de = &mut serde_json::Deserializer::from_reader(reader);
+    let upload: Upload = serde_path_to_error::deserialize_with_position(de, &offset).unwrap();
+
+    let err = &upload.rows.errors()[0];
+    assert_eq!(err.path().to_string(), "rows[1].id");
+    // Like other spans, may begin with the separator before the element.
+    let span = err.span().unwrap();
+    assert_eq!(
+        j[span.start..span.end].trim_start_matches([',', ' ']),
+        r#"{"id": -2, "tags": []}"#,
+    );
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum Shape {
+    Circle(u32),
+    Square(u32),
+}
+
+type Tagged = EnumAccessDeserializer<
+    MapAccessDeserializer<
+        MapDeserializer<'static, std::vec::IntoIter<(&'static str, i64)>, value::Error>,
+    >,
+>;
+
+fn tagged(variant: &'static str, size: i64) -> Tagged {
+    let map = MapDeserializer::new(vec![(variant, size)].into_iter());
+    EnumAccessDeserializer::new(MapAccessDeserializer::new(map))
+}
+
+#[test]
+fn test_enum_input() {
+    // A format whose deserialize_any hands over enums through visit_enum.
+    let input = vec![
+        tagged("Circle", 1),
+        tagged("Square", -2),
+        tagged("Square", 3),
+    ];
+    let de = SeqDeserializer::<_, value::Error>::new(input.into_iter());
+    let (shapes, errors) = serde_path_to_error::deserialize_lossy_seq::<_, Shape>(de).unwrap();
+
+    assert_eq!(shapes, [Shape::Circle(1), Shape::Square(3)]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path().to_string(), "[1].Square");
+}
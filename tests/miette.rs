@@ -0,0 +1,67 @@
+#![cfg(feature = "miette")]
+#![allow(dead_code)]
+
+use miette::{Diagnostic as _, NarratableReportHandler};
+use serde_derive::Deserialize;
+use serde_path_to_error::{MietteReport, Span};
+
+#[derive(Deserialize, Debug)]
+struct Package {
+    name: String,
+    version: String,
+}
+
+fn report(j: &str) -> MietteReport<serde_json::Error> {
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    let err = serde_path_to_error::deserialize::<_, Package>(jd).unwrap_err();
+    MietteReport::new(err, j)
+}
+
+fn narrate(report: &MietteReport<serde_json::Error>) -> String {
+    let mut out = String::new();
+    NarratableReportHandler::new()
+        .render_report(&mut out, report)
+        .unwrap();
+    out
+}
+
+#[test]
+fn test_label() {
+    let j = "{\n  \"name\": \"demo\",\n  \"version\": 1\n}";
+    let start = j.find('1').unwrap();
+    let report = report(j).with_span(Span::new(start - 1, start + 1));
+
+    let labels: Vec<_> = report.labels().unwrap().collect();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].label(), Some("version"));
+    assert_eq!(labels[0].offset(), start);
+    assert_eq!(labels[0].len(), 1);
+    assert_eq!(
+        report.help().unwrap().to_string(),
+        "invalid type: integer `1`, expected a string at line 3 column 14",
+    );
+
+    let expected = "\
+version: invalid type: integer `1`, expected a string at line 3 column 14
+    Diagnostic severity: error
+Begin snippet starting at line 2, column 1
+
+snippet line 2:   \"name\": \"demo\",
+snippet line 3:   \"version\": 1
+    label at line 3, column 14: version
+snippet line 4: }
+diagnostic help: invalid type: integer `1`, expected a string at line 3 column 14
+";
+    assert_eq!(narrate(&report), expected);
+}
+
+#[test]
+fn test_without_span() {
+    let report = report(r#"{"name": "demo"}"#);
+    assert!(report.labels().is_none());
+    assert!(report.source_code().is_some());
+    assert_eq!(
+        report.to_string(),
+        "missing field `version` at line 1 column 16",
+    );
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "std")]
+#![allow(dead_code)]
+
+use serde::de::{DeserializeOwned, Deserializer};
+use serde::Deserialize;
+use serde_derive::Deserialize;
+use serde_path_to_error::Error;
+
+fn embedded_json<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let s = String::deserialize(deserializer)?;
+    let jd = &mut serde_json::Deserializer::from_str(&s);
+    serde_path_to_error::deserialize(jd).map_err(Error::propagate)
+}
+
+#[derive(Deserialize, Debug)]
+struct Envelope {
+    id: u32,
+    messages: Vec<Message>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Message {
+    #[serde(deserialize_with = "embedded_json")]
+    payload: Payload,
+}
+
+#[derive(Deserialize, Debug)]
+struct Payload {
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Item {
+    #[serde(deserialize_with = "embedded_json")]
+    inner: Vec<u32>,
+}
+
+#[test]
+fn test_nested() {
+    let j = r#"{
+        "id": 1,
+        "messages": [
+            {"payload": "{\"items\": [{\"inner\": \"[1]\"}, {\"inner\": \"[2, true]\"}]}"}
+        ]
+    }"#;
+
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    let err = serde_path_to_error::deserialize::<_, Envelope>(jd).unwrap_err();
+    assert_eq!(
+        err.path().to_string(),
+        "messages[0].payload.items[1].inner[1]"
+    );
+    assert_eq!(
+        err.to_string(),
+        "messages[0].payload.items[1].inner[1]: invalid type: boolean `true`, expected u32 at line 1 column 8",
+    );
+}
+
+#[test]
+fn test_outside_of_tracking() {
+    let j = r#"{"payload": "{\"items\": 1}"}"#;
+    let err = serde_json::from_str::<Message>(j).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "items: invalid type: integer `1`, expected a sequence at line 1 column 11",
+    );
+}
@@ -0,0 +1,111 @@
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use serde_derive::Deserialize;
+use serde_path_to_error::{NodeKind, Observer, Path, Scalar};
+use std::cell::RefCell;
+
+#[derive(Default)]
+struct Events(RefCell<Vec<String>>);
+
+impl Observer for Events {
+    fn enter(&self, path: &Path, kind: NodeKind) {
+        let event = format!("enter {} {:?}", path, kind);
+        self.0.borrow_mut().push(event);
+    }
+
+    fn leaf(&self, path: &Path, scalar: Scalar) {
+        let event = format!("leaf {} {:?}", path, scalar);
+        self.0.borrow_mut().push(event);
+    }
+
+    fn exit(&self, path: &Path) {
+        let event = format!("exit {}", path);
+        self.0.borrow_mut().push(event);
+    }
+}
+
+#[test]
+fn test_observer() {
+    #[derive(Deserialize, Debug)]
+    struct Package {
+        name: String,
+        version: Option<u32>,
+        dependencies: Vec<Dependency>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    enum Dependency {
+        Path(String),
+        Registry { version: String },
+        Workspace,
+    }
+
+    let j = r#"{
+        "name": "demo",
+        "version": null,
+        "dependencies": [
+            {"Path": "../serde"},
+            {"Registry": {"version": "1.0"}},
+            "Workspace"
+        ]
+    }"#;
+
+    let events = Events::default();
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    serde_path_to_error::deserialize_observed::<_, Package>(jd, &events).unwrap();
+
+    assert_eq!(
+        *events.0.borrow(),
+        [
+            "enter . Map",
+            "leaf name Str(\"demo\")",
+            "leaf version None",
+            "enter dependencies Seq",
+            "enter dependencies[0] Enum",
+            "leaf dependencies[0].Path Str(\"../serde\")",
+            "exit dependencies[0]",
+            "enter dependencies[1] Enum",
+            "enter dependencies[1].Registry Map",
+            "leaf dependencies[1].Registry.version Str(\"1.0\")",
+            "exit dependencies[1].Registry",
+            "exit dependencies[1]",
+            "enter dependencies[2] Enum",
+            "leaf dependencies[2].Workspace Unit",
+            "exit dependencies[2]",
+            "exit dependencies",
+            "exit .",
+        ],
+    );
+}
+
+#[test]
+fn test_observer_error() {
+    #[derive(Deserialize, Debug)]
+    struct Package {
+        keywords: Vec<u8>,
+    }
+
+    let j = r#"{"keywords": [1, "two", 3]}"#;
+
+    let events = Events::default();
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    let mut track = serde_path_to_error::Track::new();
+    let pd = serde_path_to_error::Deserializer::new(jd, &mut track).with_observer(&events);
+    let result = Package::deserialize(pd);
+    assert!(result.is_err());
+    assert_eq!(track.path().to_string(), "keywords[1]");
+
+    assert_eq!(
+        *events.0.borrow(),
+        [
+            "enter . Map",
+            "enter keywords Seq",
+            "leaf keywords[0] U64(1)",
+            // serde_json rejects "two" without handing it to the u8, so there
+            // is no leaf for keywords[1].
+            "exit keywords",
+            "exit .",
+        ],
+    );
+}
@@ -0,0 +1,176 @@
+use serde_path_to_error::{Path, PathPattern, Segment};
+
+fn path(segments: &[&str]) -> Path {
+    segments
+        .iter()
+        .map(|segment| {
+            if let Ok(index) = segment.parse() {
+                Segment::Seq { index }
+            } else {
+                Segment::Map {
+                    key: (*segment).to_owned(),
+                }
+            }
+        })
+        .collect()
+}
+
+fn test(pattern: &str, matching: &[&[&str]], not_matching: &[&[&str]]) {
+    let pattern: PathPattern = pattern.parse().unwrap();
+    for segments in matching {
+        assert!(
+            pattern.matches(&path(segments)),
+            "{} !~ {:?}",
+            pattern,
+            segments
+        );
+    }
+    for segments in not_matching {
+        assert!(
+            !pattern.matches(&path(segments)),
+            "{} ~ {:?}",
+            pattern,
+            segments
+        );
+    }
+}
+
+#[test]
+fn test_literal() {
+    test(
+        "dependencies.serde",
+        &[&["dependencies", "serde"]],
+        &[
+            &["dependencies"],
+            &["dependencies", "serde", "version"],
+            &["serde"],
+        ],
+    );
+}
+
+#[test]
+fn test_any() {
+    test(
+        "dependencies.*",
+        &[&["dependencies", "serde"], &["dependencies", "0"]],
+        &[&["dependencies"], &["dependencies", "serde", "version"]],
+    );
+}
+
+#[test]
+fn test_any_depth() {
+    test(
+        "**.password",
+        &[
+            &["password"],
+            &["db", "password"],
+            &["a", "0", "b", "password"],
+        ],
+        &[&["password", "hash"], &[]],
+    );
+    test(
+        "dependencies.**",
+        &[&["dependencies"], &["dependencies", "serde", "version"]],
+        &[&["name"]],
+    );
+}
+
+#[test]
+fn test_index() {
+    test(
+        "items[*].id",
+        &[&["items", "0", "id"], &["items", "7", "id"]],
+        &[&["items", "id"], &["items", "0", "name"]],
+    );
+    test(
+        "items[2]",
+        &[&["items", "2"]],
+        &[&["items", "1"], &["items", "3"]],
+    );
+    test("[0][1]", &[&["0", "1"]], &[&["0"], &["1", "0"]]);
+}
+
+#[test]
+fn test_index_range() {
+    test(
+        "items[1..3]",
+        &[&["items", "1"], &["items", "2"]],
+        &[&["items", "0"], &["items", "3"]],
+    );
+    test(
+        "items[1..=3]",
+        &[&["items", "1"], &["items", "3"]],
+        &[&["items", "4"]],
+    );
+    test(
+        "items[2..]",
+        &[&["items", "2"], &["items", "99"]],
+        &[&["items", "1"]],
+    );
+    test(
+        "items[..2]",
+        &[&["items", "0"], &["items", "1"]],
+        &[&["items", "2"]],
+    );
+}
+
+#[test]
+fn test_segment_kinds() {
+    // An index pattern does not match a map key that looks like a number.
+    let key = Path::from_iter([
+        Segment::Map {
+            key: "items".to_owned(),
+        },
+        Segment::Map {
+            key: "0".to_owned(),
+        },
+    ]);
+    assert!(!"items[0]".parse::<PathPattern>().unwrap().matches(&key));
+    assert!("items.0".parse::<PathPattern>().unwrap().matches(&key));
+
+    // A key pattern matches an enum variant.
+    let variant = Path::from_iter([Segment::Enum {
+        variant: "Git".to_owned(),
+    }]);
+    assert!("Git".parse::<PathPattern>().unwrap().matches(&variant));
+}
+
+#[test]
+fn test_display() {
+    for pattern in [
+        "**.password",
+        "items[*].id",
+        "a[2][1..3].b",
+        "*.x[4..]",
+        "*",
+    ] {
+        assert_eq!(pattern.parse::<PathPattern>().unwrap().to_string(), pattern);
+    }
+    let pattern: PathPattern = "a[0..=1]".parse().unwrap();
+    assert_eq!(pattern.to_string(), "a[0..2]");
+
+    let max = format!("a[{0}..{0}]", usize::MAX);
+    let pattern: PathPattern = max.parse().unwrap();
+    assert_eq!(pattern.to_string(), max);
+}
+
+#[test]
+fn test_invalid() {
+    for (pattern, reason) in [
+        ("", "empty segment"),
+        ("a..b", "empty segment"),
+        ("a.", "empty segment"),
+        ("a.[0]", "empty segment"),
+        ("a[0", "unclosed `[`"),
+        ("a[x]", "invalid index"),
+        ("a[5..2]", "reversed range"),
+        ("a[5..=3]", "reversed range"),
+        ("a[0]b", "expected `.` or `[` after `]`"),
+    ] {
+        let err = pattern.parse::<PathPattern>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("invalid path pattern `{}`: {}", pattern, reason),
+        );
+    }
+}
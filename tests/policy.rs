@@ -0,0 +1,161 @@
+use serde::Serialize;
+use serde_derive::Serialize;
+use serde_path_to_error::Policy;
+use std::collections::BTreeMap as Map;
+
+fn test<T>(policy: &Policy, value: &T) -> Result<String, String>
+where
+    T: ?Sized + Serialize,
+{
+    let mut out = Vec::new();
+    let ser = &mut serde_json::Serializer::new(&mut out);
+    match policy.serialize(value, ser) {
+        Ok(()) => Ok(String::from_utf8(out).unwrap()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[derive(Serialize)]
+struct Reading {
+    id: u64,
+    values: Vec<f32>,
+    label: Option<String>,
+}
+
+#[test]
+fn test_default_allows_everything() {
+    let reading = Reading {
+        id: u64::MAX,
+        values: vec![f32::INFINITY],
+        label: None,
+    };
+    assert_eq!(
+        test(&Policy::new(), &reading).unwrap(),
+        r#"{"id":18446744073709551615,"values":[null],"label":null}"#,
+    );
+}
+
+#[test]
+fn test_finite_floats() {
+    let reading = Reading {
+        id: 1,
+        values: vec![0.5, f32::NEG_INFINITY],
+        label: None,
+    };
+    assert_eq!(
+        test(&Policy::new().finite_floats(), &reading).unwrap_err(),
+        "values[1]: float must be finite, found -inf",
+    );
+}
+
+#[test]
+fn test_safe_integers() {
+    let policy = Policy::new().safe_integers();
+    assert_eq!(
+        test(&policy, &[9007199254740991u64]).unwrap(),
+        "[9007199254740991]"
+    );
+    assert_eq!(
+        test(&policy, &[-9007199254740991i64]).unwrap(),
+        "[-9007199254740991]"
+    );
+    assert_eq!(
+        test(&policy, &[0, 9007199254740992u64]).unwrap_err(),
+        "[1]: integer must be between -9007199254740991 and 9007199254740991, found 9007199254740992",
+    );
+    assert_eq!(
+        test(&policy, &Some(i128::MIN)).unwrap_err(),
+        format!(
+            "integer must be between -9007199254740991 and 9007199254740991, found {}",
+            i128::MIN,
+        ),
+    );
+}
+
+#[test]
+fn test_max_string_len() {
+    let reading = Reading {
+        id: 1,
+        values: Vec::new(),
+        label: Some("x".repeat(11)),
+    };
+    let policy = Policy::new().max_string_len(10);
+    assert_eq!(
+        test(&policy, &reading).unwrap_err(),
+        "label: string must be at most 10 bytes long, found 11 bytes",
+    );
+    assert!(test(&policy, &"x".repeat(10)).is_ok());
+}
+
+#[test]
+fn test_string_keys() {
+    let mut limits = Map::new();
+    limits.insert(5, "five");
+    let mut map = Map::new();
+    map.insert("limits", limits);
+
+    // serde_json accepts integer keys by quoting them.
+    assert_eq!(
+        test(&Policy::new(), &map).unwrap(),
+        r#"{"limits":{"5":"five"}}"#,
+    );
+    assert_eq!(
+        test(&Policy::new().string_keys(), &map).unwrap_err(),
+        "limits.5: map key must be a string",
+    );
+
+    let mut chars = Map::new();
+    chars.insert('c', 1);
+    assert!(test(&Policy::javascript(), &chars).is_ok());
+}
+
+#[test]
+fn test_key_checked_before_written() {
+    let mut limits = Map::new();
+    limits.insert(5, "five");
+    let mut map = Map::new();
+    map.insert("limits", limits);
+
+    let mut out = Vec::new();
+    let ser = &mut serde_json::Serializer::new(&mut out);
+    let err = Policy::new()
+        .string_keys()
+        .serialize(&map, ser)
+        .unwrap_err();
+    assert_eq!(err.path().to_string(), "limits.5");
+    assert_eq!(String::from_utf8(out).unwrap(), r#"{"limits":{"#);
+}
+
+#[test]
+fn test_key_scalars() {
+    let mut map = Map::new();
+    map.insert("x".repeat(11), 1);
+    assert_eq!(
+        test(&Policy::new().max_string_len(10), &map).unwrap_err(),
+        "xxxxxxxxxxx: string must be at most 10 bytes long, found 11 bytes",
+    );
+
+    let mut map = Map::new();
+    map.insert(u64::MAX, 1);
+    assert_eq!(
+        test(&Policy::new().safe_integers(), &map).unwrap_err(),
+        "18446744073709551615: integer must be between -9007199254740991 and 9007199254740991, found 18446744073709551615",
+    );
+}
+
+#[test]
+fn test_collect_str() {
+    struct Display<'a>(&'a str);
+
+    impl Serialize for Display<'_> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self.0)
+        }
+    }
+
+    let label = "x".repeat(11);
+    assert_eq!(
+        test(&Policy::new().max_string_len(10), &[Display(&label)]).unwrap_err(),
+        "[0]: string must be at most 10 bytes long, found 11 bytes",
+    );
+}
@@ -0,0 +1,90 @@
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use serde_derive::Deserialize;
+use serde_path_to_error::{Span, Track};
+use std::cell::Cell;
+use std::collections::BTreeMap as Map;
+use std::io::{self, Read};
+
+struct CountingReader<'a> {
+    inner: &'a [u8],
+    offset: &'a Cell<usize>,
+}
+
+impl<'a> Read for CountingReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.offset.set(self.offset.get() + n);
+        Ok(n)
+    }
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    dependencies: Map<String, Dependency>,
+}
+
+#[derive(Deserialize)]
+struct Dependency {
+    version: String,
+    optional: bool,
+}
+
+fn span_of(j: &str) -> (String, Option<Span>) {
+    let offset = Cell::new(0);
+    let reader = CountingReader {
+        inner: j.as_bytes(),
+        offset: &offset,
+    };
+    let jd = &mut serde_json::Deserializer::from_reader(reader);
+    match serde_path_to_error::deserialize_with_position::<_, Package>(jd, &offset) {
+        Ok(_) => panic!("expected failure"),
+        Err(err) => (err.path().to_string(), err.span()),
+    }
+}
+
+#[test]
+fn test_invalid_type() {
+    let j = r#"{"name": "demo", "dependencies": {"serde": {"version": "1", "optional": "yes"}}}"#;
+    let (path, span) = span_of(j);
+    assert_eq!(path, "dependencies.serde.optional");
+    let span = span.unwrap();
+    assert_eq!(&j[span.start..span.end], r#" "yes""#);
+}
+
+#[test]
+fn test_missing_field() {
+    let j = r#"{"name": "demo", "dependencies": {"serde": {"version": "1"}, "syn": {}}}"#;
+    let (path, span) = span_of(j);
+    assert_eq!(path, "dependencies.serde");
+    let span = span.unwrap();
+    assert_eq!(&j[span.start..span.end], r#" {"version": "1"}"#);
+}
+
+#[test]
+fn test_track() {
+    let j = br#"{"name": true, "dependencies": {}}"#;
+    let offset = Cell::new(0);
+    let reader = CountingReader {
+        inner: j,
+        offset: &offset,
+    };
+    let jd = &mut serde_json::Deserializer::from_reader(reader);
+    let mut track = Track::new();
+    let pd = serde_path_to_error::Deserializer::new(jd, &mut track).with_position(&offset);
+    assert!(Package::deserialize(pd).is_err());
+    assert_eq!(track.span(), Some(Span::new(8, 13)));
+    assert_eq!(track.path().to_string(), "name");
+}
+
+#[test]
+fn test_without_position() {
+    let j = r#"{"name": 1, "dependencies": {}}"#;
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    let err = serde_path_to_error::deserialize::<_, Package>(jd)
+        .err()
+        .unwrap();
+    assert_eq!(err.span(), None);
+}
@@ -0,0 +1,98 @@
+use serde::Serialize;
+use serde_derive::Serialize;
+use serde_path_to_error::{PathPattern, Redact};
+use std::collections::BTreeMap as Map;
+
+#[derive(Serialize)]
+struct Config {
+    name: String,
+    credentials: Credentials,
+    services: Map<String, Service>,
+}
+
+#[derive(Serialize)]
+struct Credentials {
+    user: String,
+    token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Service {
+    url: String,
+    password: String,
+    replicas: Vec<u32>,
+}
+
+fn config() -> Config {
+    let mut services = Map::new();
+    services.insert(
+        "db".to_owned(),
+        Service {
+            url: "postgres://db".to_owned(),
+            password: "hunter2".to_owned(),
+            replicas: vec![1, 2],
+        },
+    );
+    Config {
+        name: "demo".to_owned(),
+        credentials: Credentials {
+            user: "admin".to_owned(),
+            token: None,
+        },
+        services,
+    }
+}
+
+fn test<T>(value: &T, patterns: &[&str], expected: &str)
+where
+    T: ?Sized + Serialize,
+{
+    let patterns: Vec<PathPattern> = patterns
+        .iter()
+        .map(|pattern| pattern.parse().unwrap())
+        .collect();
+    let j = serde_json::to_string(&Redact::new(value, &patterns).mask("***")).unwrap();
+    assert_eq!(j, expected);
+}
+
+#[test]
+fn test_any_depth() {
+    test(
+        &config(),
+        &["**.password"],
+        r#"{"name":"demo","credentials":{"user":"admin","token":null},"services":{"db":{"url":"postgres://db","password":"***","replicas":[1,2]}}}"#,
+    );
+}
+
+#[test]
+fn test_any_segment() {
+    test(
+        &config(),
+        &["credentials.*", "services.*.replicas.*"],
+        r#"{"name":"demo","credentials":{"user":"***","token":"***"},"services":{"db":{"url":"postgres://db","password":"hunter2","replicas":["***","***"]}}}"#,
+    );
+}
+
+#[test]
+fn test_whole_subtree() {
+    test(
+        &config(),
+        &["services"],
+        r#"{"name":"demo","credentials":{"user":"admin","token":null},"services":"***"}"#,
+    );
+}
+
+#[test]
+fn test_no_match() {
+    test(&[1, 2], &["*.password"], "[1,2]");
+    test(&"root", &["**"], r#""root""#);
+}
+
+#[test]
+fn test_invalid_pattern() {
+    let err = "a..b".parse::<PathPattern>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid path pattern `a..b`: empty segment"
+    );
+}
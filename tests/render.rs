@@ -0,0 +1,127 @@
+#![allow(dead_code)]
+
+use serde_derive::Deserialize;
+use serde_path_to_error::{Error, Report, Span};
+
+#[derive(Deserialize, Debug)]
+struct Package {
+    name: String,
+    version: String,
+}
+
+fn error(j: &str) -> Error<serde_json::Error> {
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    serde_path_to_error::deserialize::<_, Package>(jd).unwrap_err()
+}
+
+#[test]
+fn test_multiline() {
+    let j = "{\n  \"name\": \"demo\",\n  \"version\": [\n    1,\n    2\n  ]\n}";
+    let err = error(j);
+    let start = j.find('[').unwrap();
+    let end = j.find(']').unwrap() + 1;
+    let report = Report::new(&err, j).span(Span::new(start, end)).to_string();
+    let expected = "\
+error: version
+ --> 3:14
+  |
+3 |   \"version\": [
+  |              ^
+4 |     1,
+  |     ^^
+5 |     2
+  |     ^
+6 |   ]
+  |   ^ invalid type: sequence, expected a string at line 3 column 13
+";
+    assert_eq!(report, expected);
+}
+
+#[test]
+fn test_trim_whitespace() {
+    let j = "{\"name\":\t \"demo\", \"version\": null}";
+    let err = error(j);
+    let start = j.find(':').unwrap() + 1;
+    let end = j.find(',').unwrap();
+    let report = Report::new(&err, j).span(Span::new(start, end)).to_string();
+    let expected = "\
+error: version
+ --> 1:11
+  |
+1 | {\"name\":\t \"demo\", \"version\": null}
+  |         \t ^^^^^^ invalid type: null, expected a string at line 1 column 33
+";
+    assert_eq!(report, expected);
+}
+
+#[test]
+fn test_without_span() {
+    let j = "{\"name\": \"demo\"}";
+    let err = error(j);
+    let report = Report::new(&err, j).to_string();
+    let expected = "\
+error
+  = missing field `version` at line 1 column 16
+";
+    assert_eq!(report, expected);
+}
+
+#[test]
+fn test_color() {
+    let j = "{\"name\": 1}";
+    let err = error(j);
+    let report = Report::new(&err, j)
+        .span(Span::new(9, 10))
+        .color(true)
+        .to_string();
+    let expected = "\
+\x1b[1;31merror\x1b[0m\x1b[1m: name\x1b[0m
+ \x1b[1;34m-->\x1b[0m 1:10
+  \x1b[1;34m|\x1b[0m
+\x1b[1;34m1 |\x1b[0m {\"name\": 1}
+  \x1b[1;34m|\x1b[0m          \x1b[1;31m^\x1b[0m \x1b[1;31minvalid type: integer `1`, expected a string at line 1 column 10\x1b[0m
+";
+    assert_eq!(report, expected);
+}
+
+#[test]
+fn test_crlf() {
+    let j = "{\"name\": 1,\r\n\"version\": \"1.0\"}";
+    let err = error(j);
+    let start = j.find('\r').unwrap();
+    let report = Report::new(&err, j)
+        .span(Span::new(start, start + 1))
+        .to_string();
+    let expected = "\
+error: name
+ --> 1:12
+  |
+1 | {\"name\": 1,
+  |            ^ invalid type: integer `1`, expected a string at line 1 column 10
+";
+    assert_eq!(report, expected);
+
+    // No span within the source makes rendering panic.
+    for start in 0..=j.len() {
+        for end in start..=j.len() {
+            let _ = Report::new(&err, j).span(Span::new(start, end)).to_string();
+        }
+    }
+}
+
+#[test]
+fn test_past_end() {
+    let j = "{\"name\": 1}";
+    let err = error(j);
+    let report = Report::new(&err, j)
+        .span(Span::new(j.len() + 5, j.len() + 10))
+        .to_string();
+    let expected = "\
+error: name
+ --> 1:12
+  |
+1 | {\"name\": 1}
+  |            ^ invalid type: integer `1`, expected a string at line 1 column 10
+";
+    assert_eq!(report, expected);
+}
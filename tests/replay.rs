@@ -0,0 +1,180 @@
+#![cfg(feature = "std")]
+#![allow(dead_code)]
+
+use serde::Deserializer;
+use serde_derive::Deserialize;
+use std::collections::BTreeMap as Map;
+
+fn test<'de, T>(json: &'de str, expected: &str)
+where
+    T: serde::Deserialize<'de>,
+{
+    let de = &mut serde_json::Deserializer::from_str(json);
+    let result: Result<T, _> = serde_path_to_error::deserialize(de);
+    let path = match result {
+        Ok(_) => panic!("expected failure"),
+        Err(err) => err.path().to_string(),
+    };
+    assert_eq!(path, expected);
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    #[serde(flatten, deserialize_with = "serde_path_to_error::flatten")]
+    metadata: Metadata,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    keywords: Vec<String>,
+    #[serde(flatten, deserialize_with = "serde_path_to_error::flatten")]
+    links: Links,
+}
+
+#[derive(Deserialize)]
+struct Links {
+    urls: Map<String, u32>,
+}
+
+#[test]
+fn test_flatten() {
+    test::<Package>(
+        r#"{"name": "demo", "keywords": [], "urls": {"home": "x"}}"#,
+        "urls.home",
+    );
+    test::<Package>(
+        r#"{"name": "demo", "keywords": [1], "urls": {}}"#,
+        "keywords[0]",
+    );
+    test::<Vec<Package>>(
+        r#"[{"name": "demo", "keywords": [], "urls": {}}, {"name": "demo", "keywords": [""]}]"#,
+        "[1]",
+    );
+
+    let package: Package =
+        serde_json::from_str(r#"{"name": "demo", "keywords": ["a"], "urls": {}}"#).unwrap();
+    assert_eq!(package.metadata.keywords, ["a"]);
+}
+
+#[derive(Deserialize, Debug)]
+struct Config {
+    #[serde(flatten, deserialize_with = "serde_path_to_error::flatten")]
+    server: Server,
+    #[serde(flatten, deserialize_with = "serde_path_to_error::flatten")]
+    tls: Option<Tls>,
+    #[serde(flatten, deserialize_with = "serde_path_to_error::flatten")]
+    extra: Map<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Server {
+    port: u16,
+}
+
+#[derive(Deserialize, Debug)]
+struct Tls {
+    cert: String,
+}
+
+#[test]
+fn test_flatten_siblings() {
+    // The struct takes its own fields, leaving the rest to the map.
+    let j = r#"{"port": 80, "cert": "a.pem", "debug": true}"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let config: Config = serde_path_to_error::deserialize(de).unwrap();
+    assert_eq!(config.server.port, 80);
+    assert_eq!(config.tls.unwrap().cert, "a.pem");
+    let extra: Vec<&str> = config.extra.keys().map(String::as_str).collect();
+    assert_eq!(extra, ["debug"]);
+
+    test::<Config>(r#"{"port": "80"}"#, "port");
+
+    // As with Serde, a flattened `Option` whose contents fail is `None`.
+    let j = r#"{"port": 80, "cert": 1}"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let config: Config = serde_path_to_error::deserialize(de).unwrap();
+    assert!(config.tls.is_none());
+}
+
+#[derive(Deserialize, Debug)]
+enum Source {
+    Git { url: String, rev: u32 },
+    Path(PathSource),
+    Registry,
+}
+
+#[derive(Deserialize, Debug)]
+struct PathSource {
+    path: String,
+}
+
+fn tagged_source<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Source, D::Error> {
+    serde_path_to_error::internally_tagged(deserializer, "kind")
+}
+
+#[derive(Deserialize, Debug)]
+struct Dependency {
+    #[serde(deserialize_with = "tagged_source")]
+    source: Source,
+}
+
+#[test]
+fn test_internally_tagged() {
+    test::<Map<String, Dependency>>(
+        r#"{"serde": {"source": {"url": "x", "kind": "Git", "rev": "main"}}}"#,
+        "serde.source.rev",
+    );
+    test::<Dependency>(r#"{"source": {"kind": "Path", "path": 1}}"#, "source.path");
+    test::<Dependency>(r#"{"source": {"kind": "Local"}}"#, "source.kind");
+    test::<Dependency>(r#"{"source": {"path": "x"}}"#, "source");
+    test::<Dependency>(r#"{"source": "Registry"}"#, "source");
+
+    let dependency: Dependency =
+        serde_json::from_str(r#"{"source": {"kind": "Registry"}}"#).unwrap();
+    assert!(matches!(dependency.source, Source::Registry));
+
+    let dependency: Dependency =
+        serde_json::from_str(r#"{"source": {"kind": "Git", "url": "u", "rev": 1}}"#).unwrap();
+    assert!(matches!(dependency.source, Source::Git { rev: 1, .. }));
+}
+
+#[test]
+fn test_internally_tagged_with_tag_attribute() {
+    #[derive(Deserialize, Debug)]
+    #[serde(tag = "kind")]
+    enum Tagged {
+        Registry,
+    }
+
+    fn tagged<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Tagged, D::Error> {
+        serde_path_to_error::internally_tagged(deserializer, "kind")
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Dependency {
+        #[serde(deserialize_with = "tagged")]
+        source: Tagged,
+    }
+
+    let j = r#"{"source": {"kind": "Registry"}}"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let err = serde_path_to_error::deserialize::<_, Dependency>(de).unwrap_err();
+    assert_eq!(
+        err.inner().to_string(),
+        "invalid type: enum, expected internally tagged enum Tagged at line 1 column 32",
+    );
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum Version {
+    Exact(u32),
+    Detailed { major: u32, minor: u32 },
+}
+
+#[test]
+fn test_untagged_stops_at_enum() {
+    // Not replayed: the path ends at the enum rather than at `minor`.
+    test::<Map<String, Version>>(r#"{"serde": {"major": 1, "minor": "x"}}"#, "serde");
+}
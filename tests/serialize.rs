@@ -50,3 +50,110 @@ fn test_map_nonstring_key() {
 
     test(&map, "100.k");
 }
+
+#[test]
+fn test_option_and_newtype() {
+    #[derive(Serialize, Debug)]
+    struct Outer<'a> {
+        k: Option<Wrapper<'a>>,
+    }
+
+    #[derive(Serialize, Debug)]
+    struct Wrapper<'a>(Inner<'a>);
+
+    #[derive(Serialize, Debug)]
+    struct Inner<'a> {
+        refcell: &'a RefCell<String>,
+    }
+
+    let refcell = RefCell::new(String::new());
+    let outer = Outer {
+        k: Some(Wrapper(Inner { refcell: &refcell })),
+    };
+
+    let _borrowed = refcell.borrow_mut();
+    test(&outer, "k.refcell");
+}
+
+#[test]
+fn test_option_and_newtype_segments() {
+    #[derive(Serialize, Debug)]
+    struct Outer<'a> {
+        some: Option<&'a RefCell<u8>>,
+        newtype: Wrapper<'a>,
+        variant: Variant<'a>,
+        nested: Option<Wrapper<'a>>,
+    }
+
+    #[derive(Serialize, Debug)]
+    struct Wrapper<'a>(Vec<&'a RefCell<u8>>);
+
+    #[derive(Serialize, Debug)]
+    enum Variant<'a> {
+        Newtype(&'a RefCell<u8>),
+    }
+
+    let ok = RefCell::new(0);
+    let bad = RefCell::new(0);
+    let _borrowed = bad.borrow_mut();
+
+    // Options and newtypes add no segment of their own, so each of these
+    // fails at the path of the field, or of the element within it.
+    let outer = |i| Outer {
+        some: Some(if i == 0 { &bad } else { &ok }),
+        newtype: Wrapper(vec![&ok, if i == 1 { &bad } else { &ok }]),
+        variant: Variant::Newtype(if i == 2 { &bad } else { &ok }),
+        nested: Some(Wrapper(vec![if i == 3 { &bad } else { &ok }])),
+    };
+    test(&outer(0), "some");
+    test(&outer(1), "newtype[1]");
+    test(&outer(2), "variant");
+    test(&outer(3), "nested[0]");
+}
+
+#[test]
+fn test_serialize_with() {
+    use serde::ser::{Error as _, SerializeMap as _, Serializer as _};
+
+    // Type-erased values, serialized through a trait object rather than a
+    // `Serialize` impl on the map holding them.
+    trait Describe {
+        fn describe(&self) -> Result<&str, String>;
+    }
+
+    struct Broken;
+
+    impl Describe for Broken {
+        fn describe(&self) -> Result<&str, String> {
+            Err("no description".to_owned())
+        }
+    }
+
+    struct Erased<'a>(&'a dyn Describe);
+
+    impl Serialize for Erased<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            match self.0.describe() {
+                Ok(description) => serializer.serialize_str(description),
+                Err(msg) => Err(S::Error::custom(msg)),
+            }
+        }
+    }
+
+    let items: Vec<(&str, &dyn Describe)> = vec![("broken", &Broken)];
+
+    let mut out = Vec::new();
+    let ser = &mut serde_json::Serializer::new(&mut out);
+    let result = serde_path_to_error::serialize_with(ser, |ser| {
+        let mut map = ser.serialize_map(Some(items.len()))?;
+        for (key, item) in &items {
+            map.serialize_entry(key, &Erased(*item))?;
+        }
+        map.end()
+    });
+    let path = result.unwrap_err().path().to_string();
+    assert_eq!(path, "broken");
+}
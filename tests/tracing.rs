@@ -0,0 +1,154 @@
+#![cfg(feature = "tracing")]
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use serde_derive::Deserialize;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<String>>,
+}
+
+struct Fields(String);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        self.0 += &format!("{}={:?}", field.name(), value);
+    }
+}
+
+impl Subscriber for &'static Recorder {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &Attributes) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event) {
+        let mut fields = Fields(event.metadata().level().to_string());
+        event.record(&mut fields);
+        self.events.lock().unwrap().push(fields.0);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn test_tracing() {
+    #[derive(Deserialize, Debug)]
+    struct Package {
+        name: String,
+        keywords: Vec<String>,
+    }
+
+    let j = r#"{"name": "demo", "keywords": ["serde", 1]}"#;
+
+    let recorder: &'static Recorder = Box::leak(Box::default());
+    tracing::subscriber::with_default(recorder, || {
+        let de = &mut serde_json::Deserializer::from_str(j);
+        let result: Result<Package, _> = serde_path_to_error::deserialize(de);
+        assert!(result.is_err());
+    });
+
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(
+        *events,
+        [
+            "TRACE message=enter path=. kind=Map",
+            "TRACE message=leaf path=name",
+            "TRACE message=enter path=keywords kind=Seq",
+            "TRACE message=leaf path=keywords[0]",
+            format!(
+                "{} message=error path=keywords[1] error=invalid type: integer `1`, expected a string at line 1 column 40",
+                Level::WARN,
+            )
+            .as_str(),
+        ],
+    );
+}
+
+#[test]
+fn test_recovered_error() {
+    #[derive(Deserialize, Debug)]
+    struct Package {
+        keywords: serde_path_to_error::Lossy<Vec<String>>,
+    }
+
+    let j = r#"{"keywords": ["serde", 1]}"#;
+
+    let recorder: &'static Recorder = Box::leak(Box::default());
+    tracing::subscriber::with_default(recorder, || {
+        let de = &mut serde_json::Deserializer::from_str(j);
+        let package: Package = serde_path_to_error::deserialize(de).unwrap();
+        assert_eq!(package.keywords.errors().len(), 1);
+    });
+
+    let events = recorder.events.lock().unwrap();
+    assert!(events.iter().all(|event| event.starts_with("TRACE")));
+    assert!(events.contains(
+        &"TRACE message=recovered path=keywords[1] error=invalid type: integer `1`, expected a string"
+            .to_owned()
+    ));
+}
+
+#[test]
+fn test_manual_wrapper() {
+    #[derive(Deserialize, Debug)]
+    struct Package {
+        version: u32,
+    }
+
+    let j = r#"{"version": "1.0"}"#;
+
+    let recorder: &'static Recorder = Box::leak(Box::default());
+    tracing::subscriber::with_default(recorder, || {
+        let de = &mut serde_json::Deserializer::from_str(j);
+        let mut track = serde_path_to_error::Track::new();
+        let pd = serde_path_to_error::Deserializer::new(de, &mut track);
+        assert!(Package::deserialize(pd).is_err());
+    });
+
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(
+        events.last().unwrap(),
+        &format!(
+            "{} message=error path=version error=invalid type: string \"1.0\", expected u32 at line 1 column 17",
+            Level::WARN,
+        ),
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_trailing_input() {
+    let recorder: &'static Recorder = Box::leak(Box::default());
+    tracing::subscriber::with_default(recorder, || {
+        let result: Result<u32, _> = serde_path_to_error::json::from_str("1 2");
+        assert!(result.is_err());
+    });
+
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(
+        events.last().unwrap(),
+        &format!(
+            "{} message=error path=. error=trailing characters at line 1 column 3",
+            Level::WARN,
+        ),
+    );
+}
@@ -0,0 +1,73 @@
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use serde_derive::Deserialize;
+use serde_path_to_error::{Deserializer, Path, Segment, Track};
+
+#[derive(Deserialize, Debug)]
+struct Record {
+    id: u64,
+    tags: Vec<String>,
+}
+
+#[test]
+fn test_reuse() {
+    let records = [
+        r#"{"id": 1, "tags": []}"#,
+        r#"{"id": 2, "tags": ["a", 1]}"#,
+        r#"{"id": 3, "tags": []}"#,
+        r#"{"id": "4", "tags": []}"#,
+    ];
+
+    let mut track = Track::new();
+    let mut results = Vec::new();
+    for (i, record) in records.iter().enumerate() {
+        track.set_base(Path::from_iter([Segment::Map {
+            key: format!("record{}", i),
+        }]));
+        let jd = &mut serde_json::Deserializer::from_str(record);
+        let ok = Record::deserialize(Deserializer::new(jd, &mut track)).is_ok();
+        assert_eq!(track.has_error(), !ok);
+        results.push(track.take_path().to_string());
+        assert!(!track.has_error());
+        track.reset();
+    }
+
+    assert_eq!(results, [".", "record1.tags[1]", ".", "record3.id"]);
+}
+
+#[test]
+fn test_reset() {
+    let mut track = Track::new();
+    let jd = &mut serde_json::Deserializer::from_str(r#"{"id": -1}"#);
+    assert!(Record::deserialize(Deserializer::new(jd, &mut track)).is_err());
+    assert!(track.has_error());
+
+    track.reset();
+    assert!(!track.has_error());
+
+    // The first error after a reset is reported, not the one before it.
+    let jd = &mut serde_json::Deserializer::from_str(r#"{"id": 0, "tags": 0}"#);
+    assert!(Record::deserialize(Deserializer::new(jd, &mut track)).is_err());
+    assert_eq!(track.path().to_string(), "tags");
+}
+
+#[test]
+fn test_stored() {
+    // Track has no lifetime parameter, so it can be kept in a struct alongside
+    // whatever it is used with.
+    struct Session {
+        track: Track,
+        records: usize,
+    }
+
+    let mut session = Session {
+        track: Track::new(),
+        records: 0,
+    };
+    let jd = &mut serde_json::Deserializer::from_str(r#"{"id": 1, "tags": ["a"]}"#);
+    Record::deserialize(Deserializer::new(jd, &mut session.track)).unwrap();
+    session.records += 1;
+    assert!(!session.track.has_error());
+    assert_eq!(session.records, 1);
+}
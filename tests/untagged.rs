@@ -0,0 +1,157 @@
+#![allow(dead_code)]
+
+use serde::{Deserialize, Deserializer};
+use serde_derive::Deserialize;
+use serde_path_to_error::Untagged;
+use std::collections::BTreeMap as Map;
+
+#[derive(Debug)]
+enum Dependency {
+    Simple(String),
+    Git(GitDependency),
+    Detailed(DetailedDependency),
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct GitDependency {
+    git: String,
+    rev: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct DetailedDependency {
+    version: String,
+    features: Vec<String>,
+}
+
+impl<'de> Deserialize<'de> for Dependency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Untagged::new(deserializer, "Dependency")?
+            .variant("Simple", Dependency::Simple)
+            .variant("Git", Dependency::Git)
+            .variant("Detailed", Dependency::Detailed)
+            .finish()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Manifest {
+    dependencies: Map<String, Dependency>,
+}
+
+#[test]
+fn test_match() {
+    let j = r#"{"dependencies": {"a": "1.0", "b": {"git": "url"}, "c": {"version": "1", "features": []}}}"#;
+    let manifest: Manifest = serde_json::from_str(j).unwrap();
+    assert!(matches!(manifest.dependencies["a"], Dependency::Simple(_)));
+    assert!(matches!(manifest.dependencies["b"], Dependency::Git(_)));
+    assert!(matches!(
+        manifest.dependencies["c"],
+        Dependency::Detailed(_)
+    ));
+}
+
+#[test]
+fn test_aggregated() {
+    let j = r#"{"dependencies": {"serde": {"version": "1", "features": [true]}}}"#;
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    let err = serde_path_to_error::deserialize::<_, Manifest>(jd).unwrap_err();
+    assert_eq!(err.path().to_string(), "dependencies.serde");
+    assert_eq!(
+        err.inner().to_string(),
+        "data did not match any variant of untagged enum Dependency\n\
+         - Simple: invalid type: map, expected a string\n\
+         - Git: version: unknown field `version`, expected `git` or `rev`\n\
+         - Detailed: features[0]: invalid type: boolean `true`, expected a string at line 1 column 64",
+    );
+}
+
+#[test]
+fn test_closest() {
+    let j = r#"{"git": 1}"#;
+    let de = &mut serde_json::Deserializer::from_str(j);
+    let err = Untagged::new(de, "Dependency")
+        .unwrap()
+        .variant("Simple", Dependency::Simple)
+        .variant("Git", Dependency::Git)
+        .variant("Detailed", Dependency::Detailed)
+        .into_result()
+        .unwrap_err();
+
+    let names: Vec<_> = err.variants().iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["Simple", "Git", "Detailed"]);
+
+    let (name, closest) = err.closest().unwrap();
+    assert_eq!(*name, "Git");
+    assert_eq!(closest.path().to_string(), "git");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_inherits_location() {
+    use serde_path_to_error::{Observer, Path, Scalar, Segment, Track};
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct Leaves(RefCell<Vec<String>>);
+
+    impl Observer for Leaves {
+        fn leaf(&self, path: &Path, _scalar: Scalar) {
+            self.0.borrow_mut().push(path.to_string());
+        }
+    }
+
+    let j = r#"{"dependencies": {"serde": {"git": "url", "rev": 1}}}"#;
+    let leaves = Leaves::default();
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    let mut track = Track::new();
+    track.set_base(Path::from_iter([Segment::Seq { index: 4 }]));
+    let pd = serde_path_to_error::Deserializer::new(jd, &mut track).with_observer(&leaves);
+    let err = Manifest::deserialize(pd).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("\n- Git: rev: invalid type: integer `1`"));
+
+    // Each variant is tried at the location of the enum, under the same base
+    // path and observer.
+    let leaves = leaves.0.borrow();
+    assert!(leaves.contains(&"[4].dependencies.serde.git".to_owned()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_variant_paths() {
+    #[derive(Deserialize, Debug)]
+    struct Lockfile {
+        #[serde(deserialize_with = "closest_path")]
+        packages: Vec<Dependency>,
+    }
+
+    fn closest_path<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Dependency>, D::Error> {
+        struct Closest;
+
+        impl<'de> Deserialize<'de> for Closest {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let err = Untagged::new(deserializer, "Dependency")?
+                    .variant("Simple", Dependency::Simple)
+                    .variant("Git", Dependency::Git)
+                    .into_result()
+                    .unwrap_err();
+                let (_name, closest) = err.closest().unwrap();
+                Err(serde::de::Error::custom(closest.path()))
+            }
+        }
+
+        Vec::<Closest>::deserialize(deserializer).map(|_| Vec::new())
+    }
+
+    let j = r#"{"packages": [{"git": "url", "rev": 1}]}"#;
+    let jd = &mut serde_json::Deserializer::from_str(j);
+    let err = serde_path_to_error::deserialize::<_, Lockfile>(jd).unwrap_err();
+    assert_eq!(err.path().to_string(), "packages[0]");
+    assert!(err.inner().to_string().starts_with("packages[0].rev "));
+}
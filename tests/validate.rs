@@ -0,0 +1,199 @@
+use serde::Serialize;
+use serde_derive::{Deserialize, Serialize};
+use serde_path_to_error::{Rule, Rules};
+use std::cell::RefCell;
+use std::collections::BTreeMap as Map;
+
+#[derive(Serialize, Deserialize)]
+struct Config {
+    name: String,
+    port: u16,
+    ratio: f64,
+    hosts: Vec<Host>,
+    dependencies: Map<String, Dependency>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Host {
+    address: String,
+    tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Dependency {
+    version: Option<String>,
+    path: Option<String>,
+    git: Option<String>,
+}
+
+fn violations<T: Serialize>(rules: &Rules, value: &T) -> Vec<String> {
+    match rules.validate(value) {
+        Ok(()) => Vec::new(),
+        Err(violations) => violations.iter().map(ToString::to_string).collect(),
+    }
+}
+
+#[test]
+fn test_after_deserialize() {
+    let j = r#"{
+        "name": "",
+        "port": 80,
+        "ratio": 1.5,
+        "hosts": [
+            {"address": "a.example", "tags": ["x"]},
+            {"address": "b example", "tags": []}
+        ],
+        "dependencies": {
+            "serde": {"version": "1.0", "path": null, "git": null},
+            "local": {"version": "1.0", "path": "..", "git": "https://x"}
+        }
+    }"#;
+    let config: Config = serde_json::from_str(j).unwrap();
+
+    let rules = Rules::new()
+        .rule("name", Rule::non_empty())
+        .unwrap()
+        .rule("port", Rule::range(1024.0, 65535.0))
+        .unwrap()
+        .rule("ratio", Rule::range(0.0, 1.0))
+        .unwrap()
+        .rule("hosts[*].tags", Rule::non_empty())
+        .unwrap()
+        .rule(
+            "hosts[*].address",
+            Rule::string("must not contain spaces", |s| !s.contains(' ')),
+        )
+        .unwrap()
+        .rule(
+            "dependencies.*",
+            Rule::exclusive(&["version", "path", "git"]),
+        )
+        .unwrap();
+
+    assert_eq!(
+        violations(&rules, &config),
+        [
+            "name: must not be empty",
+            "port: must be between 1024 and 65535, found 80",
+            "ratio: must be between 0 and 1, found 1.5",
+            "hosts[1].address: must not contain spaces",
+            "hosts[1].tags: must not be empty",
+            "dependencies.local: `version`, `path` and `git` are mutually exclusive",
+        ],
+    );
+}
+
+#[test]
+fn test_valid() {
+    let rules = Rules::new()
+        .rule("**[*]", Rule::range(0.0, 10.0))
+        .unwrap()
+        .rule("*", Rule::non_empty())
+        .unwrap();
+    assert!(rules.validate(&vec![vec![1, 2], vec![3]]).is_ok());
+    assert_eq!(
+        violations(&rules, &vec![vec![1, 20], vec![]]),
+        [
+            "[0][1]: must be between 0 and 10, found 20",
+            "[1]: must not be empty"
+        ],
+    );
+}
+
+#[test]
+fn test_exact_range() {
+    // 2^53 + 1 rounds to 2^53 as a float.
+    let rules = Rules::new()
+        .rule("*", Rule::range(0.0, 9007199254740992.0))
+        .unwrap();
+    assert!(rules.validate(&[9007199254740992u64]).is_ok());
+    assert_eq!(
+        violations(&rules, &[9007199254740993u64]),
+        ["[0]: must be between 0 and 9007199254740992, found 9007199254740993"],
+    );
+
+    let rules = Rules::new()
+        .rule("*", Rule::range(-1.5, u128::MAX as f64))
+        .unwrap();
+    assert!(rules.validate(&(-1i8, 0.5, u128::MAX, i128::MAX)).is_ok());
+    assert_eq!(violations(&rules, &(-2i128,)).len(), 1);
+}
+
+#[test]
+fn test_display_strings() {
+    struct Version(u32, u32);
+
+    impl Serialize for Version {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(&format_args!("{}.{}", self.0, self.1))
+        }
+    }
+
+    let rules = Rules::new()
+        .rule("*", Rule::string("must be 1.x", |v| v.starts_with("1.")))
+        .unwrap();
+    assert_eq!(
+        violations(&rules, &[Version(1, 2), Version(0, 9)]),
+        ["[1]: must be 1.x"],
+    );
+}
+
+#[test]
+fn test_serialize() {
+    fn serialize<T: Serialize>(rules: &Rules, value: &T) -> Result<String, String> {
+        let mut out = Vec::new();
+        let ser = &mut serde_json::Serializer::new(&mut out);
+        match rules.serialize(value, ser) {
+            Ok(()) => Ok(String::from_utf8(out).unwrap()),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    let rules = Rules::new()
+        .rule("**[*]", Rule::range(0.0, 10.0))
+        .unwrap()
+        .rule("*", Rule::non_empty())
+        .unwrap();
+    assert_eq!(
+        serialize(&rules, &vec![vec![1, 2], vec![3]]).unwrap(),
+        "[[1,2],[3]]"
+    );
+    assert_eq!(
+        serialize(&rules, &vec![vec![1, 20], vec![]]).unwrap_err(),
+        "[0][1]: must be between 0 and 10, found 20",
+    );
+    assert_eq!(
+        serialize(&rules, &vec![vec![1], vec![]]).unwrap_err(),
+        "[1]: must not be empty",
+    );
+}
+
+#[test]
+fn test_serialize_error() {
+    let refcell = RefCell::new(1);
+    let _borrowed = refcell.borrow_mut();
+    let rules = Rules::new();
+    let value = Map::from([("k", &refcell)]);
+    assert_eq!(violations(&rules, &value), ["k: already mutably borrowed"]);
+}
+
+#[test]
+fn test_invalid_pattern() {
+    let err = Rules::new().rule("a[", Rule::non_empty()).err().unwrap();
+    assert_eq!(err.to_string(), "invalid path pattern `a[`: unclosed `[`");
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_regex() {
+    let regex = regex::Regex::new("^[a-z]+$").unwrap();
+    let rules = Rules::new().rule("**.name", Rule::regex(regex)).unwrap();
+    let value = Map::from([
+        ("a", Map::from([("name", "ok")])),
+        ("b", Map::from([("name", "Not OK")])),
+    ]);
+    assert_eq!(
+        violations(&rules, &value),
+        ["b.name: must match `^[a-z]+$`"]
+    );
+}